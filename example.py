@@ -47,6 +47,19 @@ for center_index in range(len(center_coordinates_bohr)):
         hardness=3,
     )
 
+# grid for the whole molecule in one call
+# center_indices holds for each point the index of the center it belongs to
+coordinates, weights, center_indices = numgrid.molecular_grid(
+    alpha_min,
+    alpha_max,
+    radial_precision,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_coordinates_bohr,
+    hardness=3,
+)
+
 # radial grid (LMG) using explicit basis set parameters
 radii, weights = numgrid.radial_grid_lmg(
     alpha_min={0: 0.3023, 1: 0.2753, 2: 1.185},
@@ -65,5 +78,45 @@ radii, weights = numgrid.radial_grid_lmg_bse(
 # radial grid with 100 points using Krack-Koster approach
 radii, weights = numgrid.radial_grid_kk(num_points=100)
 
+# radial grid with 100 points using Treutler-Ahlrichs M4 approach
+radii, weights = numgrid.radial_grid_ta(num_points=100, proton_charge=8)
+
+# radial grid with 100 points using Mura-Knowles approach
+radii, weights = numgrid.radial_grid_mk(num_points=100, proton_charge=8)
+
+# radial grid with 100 points using Euler-Maclaurin approach
+radii, weights = numgrid.radial_grid_em(num_points=100, proton_charge=8)
+
+# radial grid with 100 points using Becke's Gauss-Chebyshev approach
+radii, weights = numgrid.radial_grid_becke(num_points=100, proton_charge=8)
+
+# atom grid on top of any radial grid
+coordinates, weights = numgrid.atom_grid_from_radial(
+    radii,
+    weights,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_index=0,
+    center_coordinates_bohr=center_coordinates_bohr,
+    hardness=3,
+)
+
+# grid for the whole molecule using a named quality level
+coordinates, weights, center_indices = numgrid.molecular_grid_quality(
+    "fine",
+    alpha_min,
+    alpha_max,
+    proton_charges,
+    center_coordinates_bohr,
+)
+
+# SG-1 standard grid for the whole molecule
+coordinates, weights, center_indices = numgrid.standard_molecular_grid(
+    "sg1",
+    proton_charges,
+    center_coordinates_bohr,
+)
+
 # angular grid with 14 points
 coordinates, weights = numgrid.angular_grid(num_points=14)
@@ -0,0 +1,226 @@
+! Fortran interface to numgrid on top of the C interface in include/numgrid.h,
+! see there for the meaning of the arguments. Center indices are 0-based as
+! in C.
+
+module numgrid
+
+   use, intrinsic :: iso_c_binding, only: c_int, c_size_t, c_double, c_char, c_null_char
+
+   implicit none
+
+   public
+
+   integer(c_int), parameter :: NUMGRID_SUCCESS = 0
+   integer(c_int), parameter :: NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID = 1
+   integer(c_int), parameter :: NUMGRID_ERROR_UNSUPPORTED_ELEMENT = 2
+   integer(c_int), parameter :: NUMGRID_ERROR_INVALID_INPUT = 3
+   integer(c_int), parameter :: NUMGRID_ERROR_NOT_FOUND = 4
+   integer(c_int), parameter :: NUMGRID_ERROR_IO = 5
+   integer(c_int), parameter :: NUMGRID_ERROR_PARSE = 6
+   integer(c_int), parameter :: NUMGRID_ERROR_NETWORK = 7
+   integer(c_int), parameter :: NUMGRID_ERROR_BUFFER_TOO_SMALL = 8
+   integer(c_int), parameter :: NUMGRID_ERROR_INTERNAL = 9
+
+   integer(c_int), parameter :: NUMGRID_PARTITIONING_BECKE = 0
+   integer(c_int), parameter :: NUMGRID_PARTITIONING_SSF = 1
+
+   interface
+
+      function numgrid_last_error_message_c(buffer, length) result(message_length) &
+         bind(c, name='numgrid_last_error_message')
+         import :: c_char, c_size_t
+         character(kind=c_char), intent(out) :: buffer(*)
+         integer(c_size_t), value :: length
+         integer(c_size_t) :: message_length
+      end function
+
+      function numgrid_angular_grid(num_points, x, y, z, w) result(ierr) &
+         bind(c, name='numgrid_angular_grid')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         real(c_double), intent(out) :: x(*), y(*), z(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_kk(num_points, r, w) result(ierr) &
+         bind(c, name='numgrid_radial_grid_kk')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_ta(num_points, proton_charge, r, w) result(ierr) &
+         bind(c, name='numgrid_radial_grid_ta')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         integer(c_int), value :: proton_charge
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_mk(num_points, proton_charge, r, w) result(ierr) &
+         bind(c, name='numgrid_radial_grid_mk')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         integer(c_int), value :: proton_charge
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_em(num_points, proton_charge, r, w) result(ierr) &
+         bind(c, name='numgrid_radial_grid_em')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         integer(c_int), value :: proton_charge
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_becke(num_points, proton_charge, r, w) result(ierr) &
+         bind(c, name='numgrid_radial_grid_becke')
+         import :: c_int, c_size_t, c_double
+         integer(c_size_t), value :: num_points
+         integer(c_int), value :: proton_charge
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_radial_grid_lmg(alpha_min, num_alpha_min, alpha_max, &
+                                       radial_precision, proton_charge, capacity, &
+                                       r, w, num_points) result(ierr) &
+         bind(c, name='numgrid_radial_grid_lmg')
+         import :: c_int, c_size_t, c_double
+         real(c_double), intent(in) :: alpha_min(*)
+         integer(c_size_t), value :: num_alpha_min
+         real(c_double), value :: alpha_max
+         real(c_double), value :: radial_precision
+         integer(c_int), value :: proton_charge
+         integer(c_size_t), value :: capacity
+         real(c_double), intent(out) :: r(*), w(*)
+         integer(c_size_t), intent(out) :: num_points
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_atom_grid_num_points(alpha_min, num_alpha_min, alpha_max, &
+                                            radial_precision, min_num_angular_points, &
+                                            max_num_angular_points, num_centers, &
+                                            proton_charges, center_x_bohr, &
+                                            center_y_bohr, center_z_bohr, &
+                                            center_index, num_points) result(ierr) &
+         bind(c, name='numgrid_atom_grid_num_points')
+         import :: c_int, c_size_t, c_double
+         real(c_double), intent(in) :: alpha_min(*)
+         integer(c_size_t), value :: num_alpha_min
+         real(c_double), value :: alpha_max
+         real(c_double), value :: radial_precision
+         integer(c_size_t), value :: min_num_angular_points
+         integer(c_size_t), value :: max_num_angular_points
+         integer(c_size_t), value :: num_centers
+         integer(c_int), intent(in) :: proton_charges(*)
+         real(c_double), intent(in) :: center_x_bohr(*), center_y_bohr(*), center_z_bohr(*)
+         integer(c_size_t), value :: center_index
+         integer(c_size_t), intent(out) :: num_points
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_atom_grid(alpha_min, num_alpha_min, alpha_max, &
+                                 radial_precision, min_num_angular_points, &
+                                 max_num_angular_points, num_centers, &
+                                 proton_charges, center_x_bohr, center_y_bohr, &
+                                 center_z_bohr, center_index, hardness, &
+                                 partitioning, capacity, grid_x_bohr, grid_y_bohr, &
+                                 grid_z_bohr, grid_w, num_points) result(ierr) &
+         bind(c, name='numgrid_atom_grid')
+         import :: c_int, c_size_t, c_double
+         real(c_double), intent(in) :: alpha_min(*)
+         integer(c_size_t), value :: num_alpha_min
+         real(c_double), value :: alpha_max
+         real(c_double), value :: radial_precision
+         integer(c_size_t), value :: min_num_angular_points
+         integer(c_size_t), value :: max_num_angular_points
+         integer(c_size_t), value :: num_centers
+         integer(c_int), intent(in) :: proton_charges(*)
+         real(c_double), intent(in) :: center_x_bohr(*), center_y_bohr(*), center_z_bohr(*)
+         integer(c_size_t), value :: center_index
+         integer(c_size_t), value :: hardness
+         integer(c_int), value :: partitioning
+         integer(c_size_t), value :: capacity
+         real(c_double), intent(out) :: grid_x_bohr(*), grid_y_bohr(*), grid_z_bohr(*)
+         real(c_double), intent(out) :: grid_w(*)
+         integer(c_size_t), intent(out) :: num_points
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_molecular_grid_num_points(alpha_min, num_alpha_min, alpha_max, &
+                                                 radial_precision, &
+                                                 min_num_angular_points, &
+                                                 max_num_angular_points, num_centers, &
+                                                 proton_charges, center_x_bohr, &
+                                                 center_y_bohr, center_z_bohr, &
+                                                 num_points) result(ierr) &
+         bind(c, name='numgrid_molecular_grid_num_points')
+         import :: c_int, c_size_t, c_double
+         real(c_double), intent(in) :: alpha_min(*)
+         integer(c_size_t), value :: num_alpha_min
+         real(c_double), intent(in) :: alpha_max(*)
+         real(c_double), value :: radial_precision
+         integer(c_size_t), value :: min_num_angular_points
+         integer(c_size_t), value :: max_num_angular_points
+         integer(c_size_t), value :: num_centers
+         integer(c_int), intent(in) :: proton_charges(*)
+         real(c_double), intent(in) :: center_x_bohr(*), center_y_bohr(*), center_z_bohr(*)
+         integer(c_size_t), intent(out) :: num_points
+         integer(c_int) :: ierr
+      end function
+
+      function numgrid_molecular_grid(alpha_min, num_alpha_min, alpha_max, &
+                                      radial_precision, min_num_angular_points, &
+                                      max_num_angular_points, num_centers, &
+                                      proton_charges, center_x_bohr, center_y_bohr, &
+                                      center_z_bohr, hardness, partitioning, &
+                                      capacity, grid_x_bohr, grid_y_bohr, &
+                                      grid_z_bohr, grid_w, center_indices, &
+                                      num_points) result(ierr) &
+         bind(c, name='numgrid_molecular_grid')
+         import :: c_int, c_size_t, c_double
+         real(c_double), intent(in) :: alpha_min(*)
+         integer(c_size_t), value :: num_alpha_min
+         real(c_double), intent(in) :: alpha_max(*)
+         real(c_double), value :: radial_precision
+         integer(c_size_t), value :: min_num_angular_points
+         integer(c_size_t), value :: max_num_angular_points
+         integer(c_size_t), value :: num_centers
+         integer(c_int), intent(in) :: proton_charges(*)
+         real(c_double), intent(in) :: center_x_bohr(*), center_y_bohr(*), center_z_bohr(*)
+         integer(c_size_t), value :: hardness
+         integer(c_int), value :: partitioning
+         integer(c_size_t), value :: capacity
+         real(c_double), intent(out) :: grid_x_bohr(*), grid_y_bohr(*), grid_z_bohr(*)
+         real(c_double), intent(out) :: grid_w(*)
+         integer(c_size_t), intent(out) :: center_indices(*)
+         integer(c_size_t), intent(out) :: num_points
+         integer(c_int) :: ierr
+      end function
+
+   end interface
+
+contains
+
+   ! message of the last error in this thread
+   function numgrid_last_error_message() result(message)
+      character(len=:), allocatable :: message
+      character(kind=c_char) :: buffer(1024)
+      integer(c_size_t) :: length
+      integer :: i, n
+
+      length = numgrid_last_error_message_c(buffer, int(size(buffer), c_size_t))
+      n = int(min(length, int(size(buffer) - 1, c_size_t)))
+      allocate (character(len=n) :: message)
+      do i = 1, n
+         if (buffer(i) == c_null_char) exit
+         message(i:i) = buffer(i)
+      end do
+   end function
+
+end module
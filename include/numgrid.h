@@ -0,0 +1,117 @@
+#ifndef NUMGRID_H_INCLUDED
+#define NUMGRID_H_INCLUDED
+
+/*
+ * C interface to numgrid.
+ *
+ * All functions return NUMGRID_SUCCESS or one of the error codes below, the
+ * corresponding message can be retrieved with numgrid_last_error_message.
+ *
+ * Output buffers are allocated by the caller. Grid functions whose number of
+ * points is not known in advance take the capacity of the buffers, always
+ * store the number of points in num_points, and return
+ * NUMGRID_ERROR_BUFFER_TOO_SMALL without computing the grid if the capacity
+ * is not sufficient. The _num_points functions return the number of points
+ * without computing the grid.
+ *
+ * Coordinates are in bohr. alpha_min holds the smallest exponent for each
+ * angular momentum starting from s, entries which are zero or negative are
+ * skipped. For molecular grids alpha_min holds num_alpha_min entries for
+ * each center, one center after the other.
+ */
+
+#include <stddef.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+#define NUMGRID_SUCCESS 0
+#define NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID 1
+#define NUMGRID_ERROR_UNSUPPORTED_ELEMENT 2
+#define NUMGRID_ERROR_INVALID_INPUT 3
+#define NUMGRID_ERROR_NOT_FOUND 4
+#define NUMGRID_ERROR_IO 5
+#define NUMGRID_ERROR_PARSE 6
+#define NUMGRID_ERROR_NETWORK 7
+#define NUMGRID_ERROR_BUFFER_TOO_SMALL 8
+#define NUMGRID_ERROR_INTERNAL 9
+
+#define NUMGRID_PARTITIONING_BECKE 0
+#define NUMGRID_PARTITIONING_SSF 1
+
+/* Copies the message of the last error in this thread into buffer
+ * (truncated and null-terminated) and returns its full length. */
+size_t numgrid_last_error_message(char *buffer, size_t length);
+
+/* Lebedev grid, all buffers have num_points entries. */
+int numgrid_angular_grid(size_t num_points, double *x, double *y, double *z,
+                         double *w);
+
+/* Radial grids, both buffers have num_points entries. */
+int numgrid_radial_grid_kk(size_t num_points, double *r, double *w);
+int numgrid_radial_grid_ta(size_t num_points, int proton_charge, double *r,
+                           double *w);
+int numgrid_radial_grid_mk(size_t num_points, int proton_charge, double *r,
+                           double *w);
+int numgrid_radial_grid_em(size_t num_points, int proton_charge, double *r,
+                           double *w);
+int numgrid_radial_grid_becke(size_t num_points, int proton_charge, double *r,
+                              double *w);
+
+/* Lindh-Malmqvist-Gagliardi radial grid. */
+int numgrid_radial_grid_lmg(const double *alpha_min, size_t num_alpha_min,
+                            double alpha_max, double radial_precision,
+                            int proton_charge, size_t capacity, double *r,
+                            double *w, size_t *num_points);
+
+/* Number of points of numgrid_atom_grid. */
+int numgrid_atom_grid_num_points(
+    const double *alpha_min, size_t num_alpha_min, double alpha_max,
+    double radial_precision, size_t min_num_angular_points,
+    size_t max_num_angular_points, size_t num_centers,
+    const int *proton_charges, const double *center_x_bohr,
+    const double *center_y_bohr, const double *center_z_bohr,
+    size_t center_index, size_t *num_points);
+
+/* Grid of one center (0-based center_index) partitioned among all centers. */
+int numgrid_atom_grid(const double *alpha_min, size_t num_alpha_min,
+                      double alpha_max, double radial_precision,
+                      size_t min_num_angular_points,
+                      size_t max_num_angular_points, size_t num_centers,
+                      const int *proton_charges, const double *center_x_bohr,
+                      const double *center_y_bohr, const double *center_z_bohr,
+                      size_t center_index, size_t hardness, int partitioning,
+                      size_t capacity, double *grid_x_bohr,
+                      double *grid_y_bohr, double *grid_z_bohr,
+                      double *grid_w, size_t *num_points);
+
+/* Number of points of numgrid_molecular_grid. */
+int numgrid_molecular_grid_num_points(
+    const double *alpha_min, size_t num_alpha_min, const double *alpha_max,
+    double radial_precision, size_t min_num_angular_points,
+    size_t max_num_angular_points, size_t num_centers,
+    const int *proton_charges, const double *center_x_bohr,
+    const double *center_y_bohr, const double *center_z_bohr,
+    size_t *num_points);
+
+/* Grid for all centers, ordered by center, with the (0-based) index of the
+ * center which owns each point. */
+int numgrid_molecular_grid(const double *alpha_min, size_t num_alpha_min,
+                           const double *alpha_max, double radial_precision,
+                           size_t min_num_angular_points,
+                           size_t max_num_angular_points, size_t num_centers,
+                           const int *proton_charges,
+                           const double *center_x_bohr,
+                           const double *center_y_bohr,
+                           const double *center_z_bohr, size_t hardness,
+                           int partitioning, size_t capacity,
+                           double *grid_x_bohr, double *grid_y_bohr,
+                           double *grid_z_bohr, double *grid_w,
+                           size_t *center_indices, size_t *num_points);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* NUMGRID_H_INCLUDED */
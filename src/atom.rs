@@ -1,17 +1,24 @@
-#![allow(clippy::too_many_arguments)]
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
-use pyo3::prelude::*;
 use rayon::prelude::*;
 
 use std::collections::HashMap;
 
 use crate::becke_partitioning;
-use crate::bragg;
+use crate::becke_partitioning::Partitioning;
 use crate::bse;
+use crate::center_kind;
+use crate::center_kind::CenterKind;
+use crate::error::NumgridError;
+use crate::grid_batch::GridBatch;
 use crate::lebedev;
-use crate::radial;
+use crate::neighbors::CellList;
+use crate::pruning::Pruning;
+use crate::quality::Quality;
+use crate::radial::RadialScheme;
+use crate::radii::AtomicRadii;
+use crate::standard_grids::StandardGrid;
 
-#[pyfunction]
 pub fn atom_grid_bse(
     basis_set: &str,
     radial_precision: f64,
@@ -21,9 +28,12 @@ pub fn atom_grid_bse(
     center_index: usize,
     center_coordinates_bohr: Vec<(f64, f64, f64)>,
     hardness: usize,
-) -> (Vec<(f64, f64, f64)>, Vec<f64>) {
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+    check_centers(&proton_charges, center_index, &center_coordinates_bohr)?;
+
     let (alpha_min, alpha_max) =
-        bse::ang_min_and_max(basis_set, proton_charges[center_index] as usize);
+        bse::ang_min_and_max(basis_set, proton_charges[center_index] as usize)?;
 
     atom_grid(
         alpha_min,
@@ -35,10 +45,10 @@ pub fn atom_grid_bse(
         center_index,
         center_coordinates_bohr,
         hardness,
+        partitioning,
     )
 }
 
-#[pyfunction]
 pub fn atom_grid(
     alpha_min: HashMap<usize, f64>,
     alpha_max: f64,
@@ -49,68 +59,744 @@ pub fn atom_grid(
     center_index: usize,
     center_coordinates_bohr: Vec<(f64, f64, f64)>,
     hardness: usize,
-) -> (Vec<(f64, f64, f64)>, Vec<f64>) {
-    let (rs, weights_radial) = radial::radial_grid_lmg(
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+    AtomGridBuilder::new(RadialScheme::Lmg {
         alpha_min,
         alpha_max,
         radial_precision,
-        proton_charges[center_index],
-    );
+    })
+    .angular_points(min_num_angular_points, max_num_angular_points)
+    .hardness(hardness)
+    .partitioning(partitioning)
+    .build(&proton_charges, center_index, &center_coordinates_bohr)
+}
 
-    // factors match DIRAC code
-    let rb = bragg::get_bragg_angstrom(proton_charges[center_index]) / (5.0 * 0.529177249);
+/// Like `atom_grid` but built on top of a radial grid computed by any of the
+/// `radial_grid_*` functions instead of the LMG grid.
+pub fn atom_grid_from_radial(
+    radii: Vec<f64>,
+    radial_weights: Vec<f64>,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    hardness: usize,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+    AtomGridBuilder::new(RadialScheme::Explicit {
+        radii,
+        weights: radial_weights,
+    })
+    .angular_points(min_num_angular_points, max_num_angular_points)
+    .hardness(hardness)
+    .partitioning(partitioning)
+    .build(&proton_charges, center_index, &center_coordinates_bohr)
+}
 
-    let mut coordinates = Vec::new();
-    let mut weights = Vec::new();
+/// Like `atom_grid` but with radial precision, angular points, and hardness
+/// taken from a named quality level for the element of the center.
+pub fn atom_grid_quality(
+    quality: Quality,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+    quality_builder(
+        quality,
+        alpha_min,
+        alpha_max,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr,
+        partitioning,
+    )?
+    .build(&proton_charges, center_index, &center_coordinates_bohr)
+}
 
-    let pi = std::f64::consts::PI;
+// LMG builder with the parameters of a quality level for the element of the
+// center
+pub(crate) fn quality_builder(
+    quality: Quality,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    proton_charges: &[i32],
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    partitioning: Partitioning,
+) -> Result<AtomGridBuilder, NumgridError> {
+    check_centers(proton_charges, center_index, center_coordinates_bohr)?;
 
-    let cx = center_coordinates_bohr[center_index].0;
-    let cy = center_coordinates_bohr[center_index].1;
-    let cz = center_coordinates_bohr[center_index].2;
+    let p = quality.parameters(proton_charges[center_index]);
 
-    for (&r, &weight_radial) in rs.iter().zip(weights_radial.iter()) {
-        // we read the angular grid at each radial step because of pruning
-        // this can be optimized
-        let mut num_angular = max_num_angular_points;
-        if r < rb {
-            num_angular = ((max_num_angular_points as f64) * r / rb) as usize;
-            num_angular = lebedev::get_closest_num_angular(num_angular);
-            if num_angular < min_num_angular_points {
-                num_angular = min_num_angular_points;
+    Ok(AtomGridBuilder::new(RadialScheme::Lmg {
+        alpha_min,
+        alpha_max,
+        radial_precision: p.radial_precision,
+    })
+    .angular_points(p.min_num_angular_points, p.max_num_angular_points)
+    .hardness(p.hardness)
+    .partitioning(partitioning))
+}
+
+/// Atom grid with the radial grid and fixed pruning of a standard grid.
+pub fn standard_atom_grid(
+    grid: StandardGrid,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    hardness: usize,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+    AtomGridBuilder::standard(grid)
+        .hardness(hardness)
+        .partitioning(partitioning)
+        .build(&proton_charges, center_index, &center_coordinates_bohr)
+}
+
+/// Like `atom_grid` with Becke partitioning but also returns the derivatives
+/// of each weight with respect to the coordinates of every center.
+/// Grid points move rigidly with the center they belong to.
+pub fn atom_grid_gradient(
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    hardness: usize,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<Vec<(f64, f64, f64)>>), NumgridError> {
+    AtomGridBuilder::new(RadialScheme::Lmg {
+        alpha_min,
+        alpha_max,
+        radial_precision,
+    })
+    .angular_points(min_num_angular_points, max_num_angular_points)
+    .hardness(hardness)
+    .build_with_gradient(&proton_charges, center_index, &center_coordinates_bohr)
+}
+
+/// Atom grid on top of any radial scheme, with Lebedev angular grids pruned
+/// towards the nucleus and partitioned into atomic cells.
+///
+/// ```
+/// use numgrid::{AtomGridBuilder, RadialScheme};
+///
+/// let (coordinates, weights) = AtomGridBuilder::new(RadialScheme::Kk { num_points: 100 })
+///     .angular_points(86, 302)
+///     .build(&[8, 1, 1], 0, &[(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)])
+///     .unwrap();
+/// assert_eq!(coordinates.len(), weights.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct AtomGridBuilder {
+    radial_scheme: RadialScheme,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    hardness: usize,
+    partitioning: Partitioning,
+    screening: bool,
+}
+
+// centers which own grid points, in the same order as in the input
+struct GridCenters {
+    // positions in the input
+    indices: Vec<usize>,
+    // position of the center of the grid among the grid centers
+    center_index: usize,
+    proton_charges: Vec<i32>,
+    coordinates_bohr: Vec<(f64, f64, f64)>,
+    radii_angstrom: Vec<f64>,
+}
+
+impl AtomGridBuilder {
+    pub fn new(radial_scheme: RadialScheme) -> Self {
+        AtomGridBuilder {
+            radial_scheme,
+            pruning: Pruning::Bragg,
+            atomic_radii: AtomicRadii::Bragg,
+            center_kinds: None,
+            min_num_angular_points: 86,
+            max_num_angular_points: 302,
+            hardness: 3,
+            partitioning: Partitioning::Becke,
+            screening: false,
+        }
+    }
+
+    /// Radial grid and pruning of a standard grid. Hardness and partitioning
+    /// can still be changed, the angular points are fixed by the grid.
+    pub fn standard(grid: StandardGrid) -> Self {
+        AtomGridBuilder {
+            pruning: Pruning::Standard(grid),
+            ..AtomGridBuilder::new(RadialScheme::Standard(grid))
+        }
+    }
+
+    pub fn radial_scheme(mut self, radial_scheme: RadialScheme) -> Self {
+        self.radial_scheme = radial_scheme;
+        self
+    }
+
+    pub fn angular_points(mut self, min_num_points: usize, max_num_points: usize) -> Self {
+        self.min_num_angular_points = min_num_points;
+        self.max_num_angular_points = max_num_points;
+        self
+    }
+
+    pub fn hardness(mut self, hardness: usize) -> Self {
+        self.hardness = hardness;
+        self
+    }
+
+    pub fn partitioning(mut self, partitioning: Partitioning) -> Self {
+        self.partitioning = partitioning;
+        self
+    }
+
+    pub fn pruning(mut self, pruning: Pruning) -> Self {
+        self.pruning = pruning;
+        self
+    }
+
+    /// Radii used for the Becke size adjustments and for the pruning.
+    pub fn atomic_radii(mut self, atomic_radii: AtomicRadii) -> Self {
+        self.atomic_radii = atomic_radii;
+        self
+    }
+
+    /// One kind for each center. Without kinds all centers are atoms. Point
+    /// charges get an empty grid and are ignored in the partitioning.
+    pub fn center_kinds(mut self, center_kinds: Vec<CenterKind>) -> Self {
+        self.center_kinds = Some(center_kinds);
+        self
+    }
+
+    /// Only centers near each grid point enter the partitioning, which makes
+    /// grids for large molecules much faster. SSF weights do not change,
+    /// Becke weights deviate by up to about 1.0e-10. Not used for gradients.
+    pub fn screening(mut self, screening: bool) -> Self {
+        self.screening = screening;
+        self
+    }
+
+    pub fn build(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
+        self.build_points(proton_charges, center_index, center_coordinates_bohr)
+    }
+
+    /// Like `build` but returns the grid in structure-of-arrays layout.
+    pub fn build_soa(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<GridBatch, NumgridError> {
+        self.build_points(proton_charges, center_index, center_coordinates_bohr)
+    }
+
+    fn build_points<P: Points>(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<P, NumgridError> {
+        let Some(centers) =
+            self.grid_centers(proton_charges, center_index, center_coordinates_bohr)?
+        else {
+            return Ok(P::with_capacity(0));
+        };
+        let center_index = centers.center_index;
+
+        let mut points: P = self.unpartitioned(
+            centers.proton_charges[center_index],
+            centers.radii_angstrom[center_index],
+            centers.coordinates_bohr[center_index],
+        )?;
+
+        let cell_list = self.cell_list(&centers);
+        self.partition(&centers, cell_list.as_ref(), &mut points);
+
+        Ok(points)
+    }
+
+    /// Like `build` but returns an iterator over batches of at most
+    /// `batch_size` points, in the same order as `build`. Each batch is
+    /// partitioned when it is requested, so the whole grid is never held in
+    /// memory.
+    ///
+    /// ```
+    /// use numgrid::{AtomGridBuilder, RadialScheme};
+    ///
+    /// let builder = AtomGridBuilder::new(RadialScheme::Kk { num_points: 100 });
+    /// let centers = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    ///
+    /// let mut num_points = 0;
+    /// for (coordinates, weights) in builder.build_batches(&[8, 1, 1], 0, &centers, 1000).unwrap() {
+    ///     assert!(weights.len() <= 1000);
+    ///     num_points += coordinates.len();
+    /// }
+    /// assert_eq!(num_points, builder.build(&[8, 1, 1], 0, &centers).unwrap().1.len());
+    /// ```
+    pub fn build_batches(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+        batch_size: usize,
+    ) -> Result<AtomGridBatches, NumgridError> {
+        if batch_size == 0 {
+            return Err(NumgridError::InvalidInput(
+                "batch_size has to be positive".to_string(),
+            ));
+        }
+
+        let centers = self.grid_centers(proton_charges, center_index, center_coordinates_bohr)?;
+
+        let (radii, radial_weights, num_angular_points) = match &centers {
+            Some(centers) => self.shells(
+                centers.proton_charges[centers.center_index],
+                centers.radii_angstrom[centers.center_index],
+            )?,
+            None => (Vec::new(), Vec::new(), Vec::new()),
+        };
+
+        let mut angular_grids = HashMap::new();
+        for &n in &num_angular_points {
+            if let std::collections::hash_map::Entry::Vacant(entry) = angular_grids.entry(n) {
+                entry.insert(lebedev::angular_grid(n)?);
             }
         }
-        let (coordinates_angular, weights_angular) = lebedev::angular_grid(num_angular);
 
-        let wt = 4.0 * pi * weight_radial;
-        for (&xyz, &weight_angular) in coordinates_angular.iter().zip(weights_angular.iter()) {
-            let x = cx + r * xyz.0;
-            let y = cy + r * xyz.1;
-            let z = cz + r * xyz.2;
+        Ok(AtomGridBatches {
+            builder: self.clone(),
+            cell_list: centers.as_ref().and_then(|centers| self.cell_list(centers)),
+            centers,
+            num_remaining: num_angular_points.iter().sum(),
+            radii,
+            radial_weights,
+            num_angular_points,
+            angular_grids,
+            batch_size,
+            shell: 0,
+            angular_index: 0,
+        })
+    }
+
+    fn cell_list(&self, centers: &GridCenters) -> Option<CellList> {
+        if self.screening && centers.coordinates_bohr.len() > 1 {
+            Some(CellList::new(&centers.coordinates_bohr, 4.0))
+        } else {
+            None
+        }
+    }
 
-            coordinates.push((x, y, z));
-            weights.push(wt * weight_angular);
+    // multiplies the weights by the partitioning weight of the center
+    fn partition<P: Points>(
+        &self,
+        centers: &GridCenters,
+        cell_list: Option<&CellList>,
+        points: &mut P,
+    ) {
+        if centers.coordinates_bohr.len() == 1 {
+            return;
         }
+
+        points.scale_weights(|c| match cell_list {
+            Some(cell_list) => becke_partitioning::screened_partitioning_weight(
+                self.partitioning,
+                centers.center_index,
+                &centers.coordinates_bohr,
+                &centers.radii_angstrom,
+                cell_list,
+                c,
+                self.hardness,
+            ),
+            None => becke_partitioning::partitioning_weight(
+                self.partitioning,
+                centers.center_index,
+                &centers.coordinates_bohr,
+                &centers.radii_angstrom,
+                c,
+                self.hardness,
+            ),
+        });
     }
 
-    if center_coordinates_bohr.len() > 1 {
-        let w_partitioning: Vec<f64> = coordinates
+    /// Like `build` but also returns the derivatives of each weight with
+    /// respect to the coordinates of every center. Only Becke partitioning
+    /// is supported.
+    pub fn build_with_gradient(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<Vec<(f64, f64, f64)>>), NumgridError> {
+        if self.partitioning != Partitioning::Becke {
+            return Err(NumgridError::InvalidInput(
+                "weight gradients are only available for Becke partitioning".to_string(),
+            ));
+        }
+        let Some(centers) =
+            self.grid_centers(proton_charges, center_index, center_coordinates_bohr)?
+        else {
+            return Ok((Vec::new(), Vec::new(), Vec::new()));
+        };
+        let center_index = centers.center_index;
+
+        let (coordinates, mut weights): (Vec<(f64, f64, f64)>, Vec<f64>) = self.unpartitioned(
+            centers.proton_charges[center_index],
+            centers.radii_angstrom[center_index],
+            centers.coordinates_bohr[center_index],
+        )?;
+
+        let num_centers = center_coordinates_bohr.len();
+
+        if centers.coordinates_bohr.len() == 1 {
+            let gradients = vec![vec![(0.0, 0.0, 0.0); num_centers]; coordinates.len()];
+            return Ok((coordinates, weights, gradients));
+        }
+
+        let (w_partitioning, gradients): (Vec<f64>, Vec<Vec<(f64, f64, f64)>>) = coordinates
             .par_iter()
             .map(|c| {
-                becke_partitioning::partitioning_weight(
+                becke_partitioning::partitioning_weight_gradient(
                     center_index,
-                    &center_coordinates_bohr,
-                    &proton_charges,
+                    &centers.coordinates_bohr,
+                    &centers.radii_angstrom,
                     *c,
-                    hardness,
+                    self.hardness,
                 )
             })
+            .unzip();
+
+        // centers without grid do not move the weights
+        let gradients = gradients
+            .into_iter()
+            .zip(weights.iter())
+            .map(|(g, &w)| {
+                let mut gradient = vec![(0.0, 0.0, 0.0); num_centers];
+                for (&i, (dx, dy, dz)) in centers.indices.iter().zip(g) {
+                    gradient[i] = (w * dx, w * dy, w * dz);
+                }
+                gradient
+            })
             .collect();
 
         for (i, w) in weights.iter_mut().enumerate() {
             *w *= w_partitioning[i];
         }
+
+        Ok((coordinates, weights, gradients))
+    }
+
+    // number of points which `build` returns, without computing the grid
+    pub(crate) fn num_points(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<usize, NumgridError> {
+        let Some(centers) =
+            self.grid_centers(proton_charges, center_index, center_coordinates_bohr)?
+        else {
+            return Ok(0);
+        };
+        let center_index = centers.center_index;
+        let proton_charge = centers.proton_charges[center_index];
+
+        let (radii, _) = self.radial_scheme.grid(proton_charge)?;
+        let num_angular_points = self.pruning.num_angular_points(
+            proton_charge,
+            centers.radii_angstrom[center_index],
+            &radii,
+            self.min_num_angular_points,
+            self.max_num_angular_points,
+        )?;
+
+        Ok(num_angular_points.iter().sum())
+    }
+
+    // centers which take part in the partitioning, or None if the
+    // center itself has no grid
+    fn grid_centers(
+        &self,
+        proton_charges: &[i32],
+        center_index: usize,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<Option<GridCenters>, NumgridError> {
+        check_centers(proton_charges, center_index, center_coordinates_bohr)?;
+
+        let num_centers = center_coordinates_bohr.len();
+        let indices = center_kind::grid_centers(self.center_kinds.as_deref(), num_centers)?;
+
+        let Some(center_index) = indices.iter().position(|&i| i == center_index) else {
+            return Ok(None);
+        };
+
+        let proton_charges: Vec<i32> = indices.iter().map(|&i| proton_charges[i]).collect();
+        let coordinates_bohr = indices
+            .iter()
+            .map(|&i| center_coordinates_bohr[i])
+            .collect();
+        let radii_angstrom = self
+            .atomic_radii
+            .select(&indices, num_centers)?
+            .radii_angstrom(&proton_charges)?;
+
+        Ok(Some(GridCenters {
+            indices,
+            center_index,
+            proton_charges,
+            coordinates_bohr,
+            radii_angstrom,
+        }))
+    }
+
+    fn unpartitioned<P: Points>(
+        &self,
+        proton_charge: i32,
+        atomic_radius_angstrom: f64,
+        center_coordinates_bohr: (f64, f64, f64),
+    ) -> Result<P, NumgridError> {
+        let (radii, radial_weights, num_angular_points) =
+            self.shells(proton_charge, atomic_radius_angstrom)?;
+
+        atom_grid_unpartitioned(
+            &radii,
+            &radial_weights,
+            &num_angular_points,
+            center_coordinates_bohr,
+        )
     }
 
-    (coordinates, weights)
+    // radii, radial weights, and number of angular points of each shell
+    fn shells(
+        &self,
+        proton_charge: i32,
+        atomic_radius_angstrom: f64,
+    ) -> Result<(Vec<f64>, Vec<f64>, Vec<usize>), NumgridError> {
+        let (radii, radial_weights) = self.radial_scheme.grid(proton_charge)?;
+
+        let num_angular_points = self.pruning.num_angular_points(
+            proton_charge,
+            atomic_radius_angstrom,
+            &radii,
+            self.min_num_angular_points,
+            self.max_num_angular_points,
+        )?;
+
+        Ok((radii, radial_weights, num_angular_points))
+    }
+}
+
+/// Iterator over batches of partitioned grid points and weights, returned by
+/// `AtomGridBuilder::build_batches`.
+pub struct AtomGridBatches {
+    builder: AtomGridBuilder,
+    centers: Option<GridCenters>,
+    cell_list: Option<CellList>,
+    radii: Vec<f64>,
+    radial_weights: Vec<f64>,
+    num_angular_points: Vec<usize>,
+    angular_grids: HashMap<usize, (Vec<(f64, f64, f64)>, Vec<f64>)>,
+    batch_size: usize,
+    num_remaining: usize,
+    // position of the next point
+    shell: usize,
+    angular_index: usize,
+}
+
+impl Iterator for AtomGridBatches {
+    type Item = (Vec<(f64, f64, f64)>, Vec<f64>);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_points()
+    }
+}
+
+impl AtomGridBatches {
+    /// Like `next` but returns the batch in structure-of-arrays layout.
+    pub fn next_soa(&mut self) -> Option<GridBatch> {
+        self.next_points()
+    }
+
+    fn next_points<P: Points>(&mut self) -> Option<P> {
+        let centers = self.centers.as_ref()?;
+        if self.num_remaining == 0 {
+            return None;
+        }
+        let center_coordinates_bohr = centers.coordinates_bohr[centers.center_index];
+
+        let num_points = self.batch_size.min(self.num_remaining);
+        let mut points = P::with_capacity(num_points);
+
+        while points.len() < num_points {
+            let (coordinates_angular, weights_angular) =
+                &self.angular_grids[&self.num_angular_points[self.shell]];
+            let end = (self.angular_index + num_points - points.len()).min(weights_angular.len());
+
+            for k in self.angular_index..end {
+                let (xyz, w) = grid_point(
+                    center_coordinates_bohr,
+                    self.radii[self.shell],
+                    self.radial_weights[self.shell],
+                    coordinates_angular[k],
+                    weights_angular[k],
+                );
+                points.push(xyz, w);
+            }
+
+            if end == weights_angular.len() {
+                self.shell += 1;
+                self.angular_index = 0;
+            } else {
+                self.angular_index = end;
+            }
+        }
+        self.num_remaining -= num_points;
+
+        self.builder
+            .partition(centers, self.cell_list.as_ref(), &mut points);
+
+        Some(points)
+    }
+}
+
+fn check_centers(
+    proton_charges: &[i32],
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+) -> Result<(), NumgridError> {
+    if proton_charges.len() != center_coordinates_bohr.len() {
+        return Err(NumgridError::InvalidInput(format!(
+            "{} proton charges but {} center coordinates",
+            proton_charges.len(),
+            center_coordinates_bohr.len()
+        )));
+    }
+    if center_index >= center_coordinates_bohr.len() {
+        return Err(NumgridError::InvalidInput(format!(
+            "center_index {} out of range for {} centers",
+            center_index,
+            center_coordinates_bohr.len()
+        )));
+    }
+    Ok(())
+}
+
+// atom-centered grid before partitioning into atomic cells
+fn atom_grid_unpartitioned<P: Points>(
+    rs: &[f64],
+    weights_radial: &[f64],
+    num_angular_points: &[usize],
+    center_coordinates_bohr: (f64, f64, f64),
+) -> Result<P, NumgridError> {
+    let mut points = P::with_capacity(0);
+
+    for ((&r, &weight_radial), &num_angular) in rs
+        .iter()
+        .zip(weights_radial.iter())
+        .zip(num_angular_points.iter())
+    {
+        // we read the angular grid at each radial step because of pruning
+        // this can be optimized
+        let (coordinates_angular, weights_angular) = lebedev::angular_grid(num_angular)?;
+
+        for (&xyz, &weight_angular) in coordinates_angular.iter().zip(weights_angular.iter()) {
+            let (point, weight) = grid_point(
+                center_coordinates_bohr,
+                r,
+                weight_radial,
+                xyz,
+                weight_angular,
+            );
+            points.push(point, weight);
+        }
+    }
+
+    Ok(points)
+}
+
+// grid points stored either as (coordinates, weights) or as `GridBatch`, so
+// that both layouts are computed without conversion
+trait Points: Send {
+    fn with_capacity(capacity: usize) -> Self;
+    fn len(&self) -> usize;
+    fn push(&mut self, point: (f64, f64, f64), weight: f64);
+    // multiplies each weight by f of its point
+    fn scale_weights<F: Fn((f64, f64, f64)) -> f64 + Sync>(&mut self, f: F);
+}
+
+impl Points for (Vec<(f64, f64, f64)>, Vec<f64>) {
+    fn with_capacity(capacity: usize) -> Self {
+        (Vec::with_capacity(capacity), Vec::with_capacity(capacity))
+    }
+
+    fn len(&self) -> usize {
+        self.1.len()
+    }
+
+    fn push(&mut self, point: (f64, f64, f64), weight: f64) {
+        self.0.push(point);
+        self.1.push(weight);
+    }
+
+    fn scale_weights<F: Fn((f64, f64, f64)) -> f64 + Sync>(&mut self, f: F) {
+        let (coordinates, weights) = self;
+        weights
+            .par_iter_mut()
+            .zip(coordinates.par_iter())
+            .for_each(|(w, c)| *w *= f(*c));
+    }
+}
+
+impl Points for GridBatch {
+    fn with_capacity(capacity: usize) -> Self {
+        GridBatch::with_capacity(capacity)
+    }
+
+    fn len(&self) -> usize {
+        GridBatch::len(self)
+    }
+
+    fn push(&mut self, point: (f64, f64, f64), weight: f64) {
+        GridBatch::push(self, point, weight)
+    }
+
+    fn scale_weights<F: Fn((f64, f64, f64)) -> f64 + Sync>(&mut self, f: F) {
+        let GridBatch { x, y, z, w } = self;
+        w.par_iter_mut()
+            .enumerate()
+            .for_each(|(i, w)| *w *= f((x[i], y[i], z[i])));
+    }
+}
+
+// point and weight (before partitioning) from a radial and an angular point
+fn grid_point(
+    center_coordinates_bohr: (f64, f64, f64),
+    r: f64,
+    weight_radial: f64,
+    xyz: (f64, f64, f64),
+    weight_angular: f64,
+) -> ((f64, f64, f64), f64) {
+    let (cx, cy, cz) = center_coordinates_bohr;
+    let wt = 4.0 * std::f64::consts::PI * weight_radial;
+    (
+        (cx + r * xyz.0, cy + r * xyz.1, cz + r * xyz.2),
+        wt * weight_angular,
+    )
 }
@@ -0,0 +1,510 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use crate::bse;
+use crate::error::NumgridError;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Format {
+    Gaussian,
+    NWChem,
+    Turbomole,
+    Json,
+}
+
+const ELEMENT_SYMBOLS: [&str; 118] = [
+    "H", "He", "Li", "Be", "B", "C", "N", "O", "F", "Ne", "Na", "Mg", "Al", "Si", "P", "S", "Cl",
+    "Ar", "K", "Ca", "Sc", "Ti", "V", "Cr", "Mn", "Fe", "Co", "Ni", "Cu", "Zn", "Ga", "Ge", "As",
+    "Se", "Br", "Kr", "Rb", "Sr", "Y", "Zr", "Nb", "Mo", "Tc", "Ru", "Rh", "Pd", "Ag", "Cd", "In",
+    "Sn", "Sb", "Te", "I", "Xe", "Cs", "Ba", "La", "Ce", "Pr", "Nd", "Pm", "Sm", "Eu", "Gd", "Tb",
+    "Dy", "Ho", "Er", "Tm", "Yb", "Lu", "Hf", "Ta", "W", "Re", "Os", "Ir", "Pt", "Au", "Hg", "Tl",
+    "Pb", "Bi", "Po", "At", "Rn", "Fr", "Ra", "Ac", "Th", "Pa", "U", "Np", "Pu", "Am", "Cm", "Bk",
+    "Cf", "Es", "Fm", "Md", "No", "Lr", "Rf", "Db", "Sg", "Bh", "Hs", "Mt", "Ds", "Rg", "Cn", "Nh",
+    "Fl", "Mc", "Lv", "Ts", "Og",
+];
+
+fn element_symbol(proton_charge: i32) -> Result<&'static str, NumgridError> {
+    if proton_charge < 1 || proton_charge as usize > ELEMENT_SYMBOLS.len() {
+        return Err(NumgridError::UnsupportedElement(proton_charge));
+    }
+    Ok(ELEMENT_SYMBOLS[(proton_charge - 1) as usize])
+}
+
+/// Smallest exponent for each angular momentum and the largest exponent of
+/// the given element, read from a basis set file in Gaussian (.gbs, .g94),
+/// NWChem (.nw), Turbomole, or BSE JSON (.json) format.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn alpha_min_and_max(
+    file_name: &str,
+    proton_charge: i32,
+) -> Result<(HashMap<usize, f64>, f64), NumgridError> {
+    let contents = match fs::read_to_string(file_name) {
+        Ok(v) => v,
+        Err(e) => {
+            return Err(NumgridError::Io(format!(
+                "could not read {}: {}",
+                file_name, e
+            )))
+        }
+    };
+
+    let symbol = element_symbol(proton_charge)?;
+
+    let shells = match detect_format(file_name, &contents)? {
+        Format::Gaussian => parse_gaussian(&contents, symbol),
+        Format::NWChem => parse_nwchem(&contents, symbol),
+        Format::Turbomole => parse_turbomole(&contents, symbol),
+        Format::Json => return bse::ang_min_and_max(file_name, proton_charge as usize),
+    };
+
+    if shells.is_empty() {
+        return Err(NumgridError::NotFound(format!(
+            "element {} in {}",
+            symbol, file_name
+        )));
+    }
+
+    Ok(exponent_extrema(&shells))
+}
+
+fn detect_format(file_name: &str, contents: &str) -> Result<Format, NumgridError> {
+    let extension = Path::new(file_name)
+        .extension()
+        .map(|e| e.to_string_lossy().to_lowercase());
+
+    match extension.as_deref() {
+        Some("gbs") | Some("g94") => return Ok(Format::Gaussian),
+        Some("nw") | Some("nwchem") => return Ok(Format::NWChem),
+        Some("json") => return Ok(Format::Json),
+        _ => {}
+    }
+
+    let mut lines = contents.lines().map(|l| l.trim()).filter(|l| {
+        !l.is_empty() && !l.starts_with('!') && !l.starts_with('#') && !l.starts_with('$')
+    });
+
+    if contents
+        .lines()
+        .any(|l| l.trim_start().starts_with("$basis"))
+    {
+        Ok(Format::Turbomole)
+    } else if lines.any(|l| l.to_lowercase().starts_with("basis")) {
+        Ok(Format::NWChem)
+    } else if contents.lines().any(|l| l.trim() == "****") {
+        Ok(Format::Gaussian)
+    } else {
+        Err(NumgridError::Parse(format!(
+            "could not detect the basis set format of {}",
+            file_name
+        )))
+    }
+}
+
+// angular momenta of a shell label; SP (or L) shells contribute to both s and p
+fn angular_momenta(label: &str) -> Option<Vec<usize>> {
+    let label = label.to_uppercase();
+    if label == "L" {
+        return Some(vec![0, 1]);
+    }
+
+    // J is not used, to avoid confusion with I
+    let letters = "SPDFGHIKM";
+    let mut momenta = Vec::new();
+    for c in label.chars() {
+        momenta.push(letters.find(c)?);
+    }
+    if momenta.is_empty() {
+        None
+    } else {
+        Some(momenta)
+    }
+}
+
+#[test]
+fn test_angular_momenta() {
+    assert_eq!(angular_momenta("s"), Some(vec![0]));
+    assert_eq!(angular_momenta("D"), Some(vec![2]));
+    assert_eq!(angular_momenta("SP"), Some(vec![0, 1]));
+    assert_eq!(angular_momenta("L"), Some(vec![0, 1]));
+    assert_eq!(angular_momenta("K"), Some(vec![7]));
+    assert_eq!(angular_momenta("X"), None);
+    assert_eq!(angular_momenta("1.00"), None);
+}
+
+// Fortran-style exponents like 0.1172D+05 are also accepted
+fn parse_exponent(token: &str) -> Option<f64> {
+    token.replace(['D', 'd'], "E").parse().ok()
+}
+
+// the exponent is the first column, any further columns hold the
+// (possibly general) contraction coefficients
+fn first_column(line: &str) -> Option<f64> {
+    line.split_whitespace().next().and_then(parse_exponent)
+}
+
+fn exponent_extrema(shells: &[(Vec<usize>, Vec<f64>)]) -> (HashMap<usize, f64>, f64) {
+    let mut alpha_max = f64::MIN;
+    let mut alpha_min = HashMap::new();
+
+    for (momenta, exponents) in shells {
+        for &exponent in exponents {
+            alpha_max = alpha_max.max(exponent);
+            for &l in momenta {
+                let s = alpha_min.entry(l).or_insert(f64::MAX);
+                if exponent < *s {
+                    *s = exponent;
+                }
+            }
+        }
+    }
+
+    (alpha_min, alpha_max)
+}
+
+fn strip_comment<'a>(line: &'a str, markers: &[char]) -> &'a str {
+    match line.find(markers) {
+        Some(i) => &line[..i],
+        None => line,
+    }
+}
+
+fn is_symbol(token: &str, symbol: &str) -> bool {
+    token.trim_start_matches('-').eq_ignore_ascii_case(symbol)
+}
+
+// Gaussian 94 format:
+//   O     0
+//   S   9   1.00
+//     11720.0000000              0.0007100
+//   ...
+//   ****
+fn parse_gaussian(contents: &str, symbol: &str) -> Vec<(Vec<usize>, Vec<f64>)> {
+    let mut shells = Vec::new();
+    let mut in_element = false;
+    let mut lines = contents.lines().map(|l| strip_comment(l, &['!']).trim());
+
+    while let Some(line) = lines.next() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        if tokens[0] == "****" {
+            in_element = false;
+            continue;
+        }
+
+        if !in_element {
+            // element header: one or more atom labels terminated by 0
+            if tokens.len() >= 2 && tokens[tokens.len() - 1] == "0" {
+                in_element = tokens[..tokens.len() - 1]
+                    .iter()
+                    .any(|t| is_symbol(t, symbol));
+            }
+            continue;
+        }
+
+        // shell header: label, number of primitives, scale factor
+        if let (Some(momenta), Some(num_primitives)) = (
+            angular_momenta(tokens[0]),
+            tokens.get(1).and_then(|t| t.parse::<usize>().ok()),
+        ) {
+            let exponents: Vec<f64> = (&mut lines)
+                .take(num_primitives)
+                .filter_map(first_column)
+                .collect();
+            shells.push((momenta, exponents));
+        }
+    }
+
+    shells
+}
+
+// NWChem format:
+//   BASIS "ao basis" PRINT
+//   O    S
+//     11720.0000000              0.0007100             -0.0001600
+//   ...
+//   END
+fn parse_nwchem(contents: &str, symbol: &str) -> Vec<(Vec<usize>, Vec<f64>)> {
+    let mut shells = Vec::new();
+    let mut in_basis = false;
+    let mut current: Option<(Vec<usize>, Vec<f64>)> = None;
+
+    for line in contents.lines().map(|l| strip_comment(l, &['#']).trim()) {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        let keyword = tokens[0].to_lowercase();
+
+        if !in_basis {
+            // ECP and other blocks are skipped
+            in_basis = keyword == "basis";
+            continue;
+        }
+
+        if keyword == "end" {
+            shells.extend(current.take());
+            in_basis = false;
+            continue;
+        }
+
+        if let Some(exponent) = first_column(line) {
+            if let Some((_, exponents)) = current.as_mut() {
+                exponents.push(exponent);
+            }
+            continue;
+        }
+
+        // shell header: atom tag and shell label
+        shells.extend(current.take());
+        if tokens.len() >= 2 && is_symbol(tokens[0], symbol) {
+            if let Some(momenta) = angular_momenta(tokens[1]) {
+                current = Some((momenta, Vec::new()));
+            }
+        }
+    }
+    shells.extend(current);
+
+    shells
+}
+
+// Turbomole format:
+//   $basis
+//   *
+//   o cc-pVDZ
+//   *
+//      9  s
+//     11720.000000      0.71000000000E-03
+//   ...
+//   *
+//   $end
+fn parse_turbomole(contents: &str, symbol: &str) -> Vec<(Vec<usize>, Vec<f64>)> {
+    let mut shells = Vec::new();
+    let mut in_basis = false;
+    let mut in_element = false;
+    let mut lines = contents.lines().map(|l| strip_comment(l, &['#']).trim());
+
+    while let Some(line) = lines.next() {
+        let tokens: Vec<&str> = line.split_whitespace().collect();
+        if tokens.is_empty() {
+            continue;
+        }
+
+        if tokens[0].starts_with('$') {
+            in_basis = tokens[0] == "$basis";
+            in_element = false;
+            continue;
+        }
+
+        if !in_basis || tokens[0] == "*" {
+            continue;
+        }
+
+        // shell header: number of primitives and shell label
+        if let (Some(num_primitives), Some(momenta)) = (
+            tokens[0].parse::<usize>().ok(),
+            tokens.get(1).and_then(|t| angular_momenta(t)),
+        ) {
+            let exponents: Vec<f64> = (&mut lines)
+                .take(num_primitives)
+                .filter_map(first_column)
+                .collect();
+            if in_element {
+                shells.push((momenta, exponents));
+            }
+            continue;
+        }
+
+        // element header: symbol and basis set name
+        in_element = is_symbol(tokens[0], symbol);
+    }
+
+    shells
+}
+
+#[cfg(test)]
+const GAUSSIAN_6_31GS: &str = "
+! 6-31G*
+****
+H     0
+S   3   1.00
+      0.1873113696D+02       0.3349460434D-01
+      0.2825394365D+01       0.2347269535D+00
+      0.6401216923D+00       0.8137573261D+00
+S   1   1.00
+      0.1612777588D+00       1.0000000
+****
+O     0
+S   6   1.00
+      0.5484671660D+04       0.1831074430D-02
+      0.8252349460D+03       0.1395017220D-01
+      0.1880469580D+03       0.6844507810D-01
+      0.5296450000D+02       0.2327143360D+00
+      0.1689757040D+02       0.4701928980D+00
+      0.5799635340D+01       0.3585208530D+00
+SP   3   1.00
+      0.1553961625D+02      -0.1107775495D+00       0.7087426823D-01
+      0.3599933586D+01      -0.1480262627D+00       0.3397528391D+00
+      0.1013761750D+01       0.1130767015D+01       0.7271585773D+00
+SP   1   1.00
+      0.2700058226D+00       0.1000000000D+01       0.1000000000D+01
+D   1   1.00
+      0.8000000000D+00       1.0000000
+****
+";
+
+#[test]
+fn test_parse_gaussian() {
+    let (alpha_min, alpha_max) = exponent_extrema(&parse_gaussian(GAUSSIAN_6_31GS, "O"));
+    assert_eq!(alpha_max, 5484.67166);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.2700058226);
+    assert_eq!(alpha_min[&1], 0.2700058226);
+    assert_eq!(alpha_min[&2], 0.8);
+
+    let (alpha_min, alpha_max) = exponent_extrema(&parse_gaussian(GAUSSIAN_6_31GS, "H"));
+    assert_eq!(alpha_max, 18.73113696);
+    assert_eq!(alpha_min.len(), 1);
+    assert_eq!(alpha_min[&0], 0.1612777588);
+
+    assert!(parse_gaussian(GAUSSIAN_6_31GS, "C").is_empty());
+}
+
+#[cfg(test)]
+const NWCHEM_6_31GS: &str = "
+#  6-31G*
+BASIS \"ao basis\" PRINT
+#BASIS SET: (10s,4p,1d) -> [3s,2p,1d]
+O    S
+   5484.6716600              0.0018311
+    825.2349460              0.0139502
+    188.0469580              0.0684451
+     52.9645000              0.2327143
+     16.8975704              0.4701929
+      5.7996353              0.3585209
+O    SP
+     15.5396162             -0.1107775              0.0708743
+      3.5999336             -0.1480263              0.3397528
+      1.0137618              1.1307670              0.7271586
+O    SP
+      0.2700058              1.0000000              1.0000000
+O    D
+      0.8000000              1.0000000
+END
+ECP
+O nelec 2
+O ul
+2      1.0000000              0.0000000
+END
+";
+
+#[test]
+fn test_parse_nwchem() {
+    let (alpha_min, alpha_max) = exponent_extrema(&parse_nwchem(NWCHEM_6_31GS, "O"));
+    assert_eq!(alpha_max, 5484.67166);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.2700058);
+    assert_eq!(alpha_min[&1], 0.2700058);
+    assert_eq!(alpha_min[&2], 0.8);
+
+    assert!(parse_nwchem(NWCHEM_6_31GS, "H").is_empty());
+}
+
+#[cfg(test)]
+const TURBOMOLE_DEF_SVP: &str = "
+$basis
+*
+h def-SV(P)
+# hydrogen
+*
+   3  s
+     13.010701000      0.19682158000E-01
+      1.9622572000     0.13796524000
+      0.44453796000    0.47831935000
+   1  s
+      0.12194962000    1.0000000000
+*
+o def-SV(P)
+*
+   5  s
+   2266.1767785     -0.53431809926E-02
+    340.87010191    -0.39890039230E-01
+     77.363135167   -0.17853911985
+     21.479644940   -0.46427684959
+      6.6589433124  -0.44309745172
+   1  s
+      0.80975975668    1.0000000000
+   1  s
+      0.25530772234    1.0000000000
+   3  p
+     17.721504317      0.43394573193E-01
+      3.8635505440     0.23094120765
+      1.0480920883     0.51375311064
+   1  p
+      0.27641544411    1.0000000000
+   1  d
+      1.2000000        1.0000000
+*
+$ecp
+*
+o def-ecp
+*
+$end
+";
+
+#[test]
+fn test_parse_turbomole() {
+    let (alpha_min, alpha_max) = exponent_extrema(&parse_turbomole(TURBOMOLE_DEF_SVP, "O"));
+    assert_eq!(alpha_max, 2266.1767785);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.25530772234);
+    assert_eq!(alpha_min[&1], 0.27641544411);
+    assert_eq!(alpha_min[&2], 1.2);
+
+    let (alpha_min, alpha_max) = exponent_extrema(&parse_turbomole(TURBOMOLE_DEF_SVP, "H"));
+    assert_eq!(alpha_max, 13.010701);
+    assert_eq!(alpha_min.len(), 1);
+    assert_eq!(alpha_min[&0], 0.12194962);
+
+    assert!(parse_turbomole(TURBOMOLE_DEF_SVP, "C").is_empty());
+}
+
+#[test]
+fn test_alpha_min_and_max_json() {
+    // the same 6-31G* oxygen as above, in BSE JSON format
+    let (alpha_min, alpha_max) = alpha_min_and_max("tests/basis/6-31g_st_.0.json", 8).unwrap();
+    assert_eq!(alpha_max, 5484.67166);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.2700058226);
+    assert_eq!(alpha_min[&1], 0.2700058226);
+    assert_eq!(alpha_min[&2], 0.8);
+
+    assert!(alpha_min_and_max("tests/basis/6-31g_st_.0.json", 1).is_err());
+}
+
+#[test]
+fn test_detect_format() {
+    assert_eq!(detect_format("basis.gbs", "").unwrap(), Format::Gaussian);
+    assert_eq!(detect_format("basis.nw", "").unwrap(), Format::NWChem);
+    assert_eq!(detect_format("basis.json", "").unwrap(), Format::Json);
+    assert_eq!(
+        detect_format("basis", GAUSSIAN_6_31GS).unwrap(),
+        Format::Gaussian
+    );
+    assert_eq!(
+        detect_format("basis", NWCHEM_6_31GS).unwrap(),
+        Format::NWChem
+    );
+    assert_eq!(
+        detect_format("basis", "$basis\n*\n$end\n").unwrap(),
+        Format::Turbomole
+    );
+    assert!(detect_format("basis", "").is_err());
+}
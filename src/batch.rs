@@ -0,0 +1,216 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use std::collections::BTreeMap;
+
+use crate::error::NumgridError;
+
+/// Spatially compact group of grid points which all belong to the same
+/// center.
+#[cfg_attr(feature = "python", pyclass(get_all, frozen))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct Batch {
+    /// Center which owns the points.
+    pub center_index: usize,
+    /// Positions of the points in the input grid.
+    pub point_indices: Vec<usize>,
+    pub coordinates_bohr: Vec<(f64, f64, f64)>,
+    pub weights: Vec<f64>,
+    /// Corners of the axis-aligned bounding box.
+    pub box_min: (f64, f64, f64),
+    pub box_max: (f64, f64, f64),
+    /// Sphere around the center of the bounding box which contains all points.
+    pub sphere_center: (f64, f64, f64),
+    pub sphere_radius: f64,
+}
+
+/// Splits a grid into batches of at most `max_batch_size` points. Points of
+/// each center are sorted along a Hilbert curve and cut into consecutive
+/// pieces, so all batches except the last one of each center are full.
+/// Batches are ordered by center.
+pub fn batch_grid(
+    coordinates_bohr: Vec<(f64, f64, f64)>,
+    weights: Vec<f64>,
+    center_indices: Vec<usize>,
+    max_batch_size: usize,
+) -> Result<Vec<Batch>, NumgridError> {
+    if weights.len() != coordinates_bohr.len() || center_indices.len() != coordinates_bohr.len() {
+        return Err(NumgridError::InvalidInput(format!(
+            "{} coordinates, {} weights, and {} center indices",
+            coordinates_bohr.len(),
+            weights.len(),
+            center_indices.len()
+        )));
+    }
+    if max_batch_size == 0 {
+        return Err(NumgridError::InvalidInput(
+            "max_batch_size has to be positive".to_string(),
+        ));
+    }
+
+    // points of each center in one pass, ordered by center
+    let mut points_by_center: BTreeMap<usize, Vec<usize>> = BTreeMap::new();
+    for (i, &center_index) in center_indices.iter().enumerate() {
+        points_by_center.entry(center_index).or_default().push(i);
+    }
+
+    let mut batches = Vec::new();
+    for (center_index, mut points) in points_by_center {
+        let (box_min, box_max) = bounding_box(&coordinates_bohr, &points);
+        let keys: Vec<u64> = points
+            .iter()
+            .map(|&i| hilbert_key(coordinates_bohr[i], box_min, box_max))
+            .collect();
+        let mut order: Vec<usize> = (0..points.len()).collect();
+        order.sort_by_key(|&k| keys[k]);
+        points = order.iter().map(|&k| points[k]).collect();
+
+        for chunk in points.chunks(max_batch_size) {
+            batches.push(make_batch(center_index, chunk, &coordinates_bohr, &weights));
+        }
+    }
+
+    Ok(batches)
+}
+
+fn make_batch(
+    center_index: usize,
+    point_indices: &[usize],
+    coordinates_bohr: &[(f64, f64, f64)],
+    weights: &[f64],
+) -> Batch {
+    let (box_min, box_max) = bounding_box(coordinates_bohr, point_indices);
+    let sphere_center = (
+        0.5 * (box_min.0 + box_max.0),
+        0.5 * (box_min.1 + box_max.1),
+        0.5 * (box_min.2 + box_max.2),
+    );
+    let sphere_radius = point_indices
+        .iter()
+        .map(|&i| {
+            let c = coordinates_bohr[i];
+            ((c.0 - sphere_center.0).powi(2)
+                + (c.1 - sphere_center.1).powi(2)
+                + (c.2 - sphere_center.2).powi(2))
+            .sqrt()
+        })
+        .fold(0.0, f64::max);
+
+    Batch {
+        center_index,
+        point_indices: point_indices.to_vec(),
+        coordinates_bohr: point_indices.iter().map(|&i| coordinates_bohr[i]).collect(),
+        weights: point_indices.iter().map(|&i| weights[i]).collect(),
+        box_min,
+        box_max,
+        sphere_center,
+        sphere_radius,
+    }
+}
+
+fn bounding_box(
+    coordinates_bohr: &[(f64, f64, f64)],
+    point_indices: &[usize],
+) -> ((f64, f64, f64), (f64, f64, f64)) {
+    let mut min = (f64::MAX, f64::MAX, f64::MAX);
+    let mut max = (f64::MIN, f64::MIN, f64::MIN);
+    for &i in point_indices {
+        let c = coordinates_bohr[i];
+        min = (min.0.min(c.0), min.1.min(c.1), min.2.min(c.2));
+        max = (max.0.max(c.0), max.1.max(c.1), max.2.max(c.2));
+    }
+    (min, max)
+}
+
+const HILBERT_BITS: u32 = 21;
+
+// position along a Hilbert curve through the box, coordinates are mapped
+// onto a grid of 2^21 points in each direction
+fn hilbert_key(point: (f64, f64, f64), box_min: (f64, f64, f64), box_max: (f64, f64, f64)) -> u64 {
+    let n = ((1_u32 << HILBERT_BITS) - 1) as f64;
+    let scale = |x: f64, lo: f64, hi: f64| {
+        if hi > lo {
+            ((x - lo) / (hi - lo) * n).round().clamp(0.0, n) as u32
+        } else {
+            0
+        }
+    };
+    let mut axes = [
+        scale(point.0, box_min.0, box_max.0),
+        scale(point.1, box_min.1, box_max.1),
+        scale(point.2, box_min.2, box_max.2),
+    ];
+    axes_to_transpose(&mut axes, HILBERT_BITS);
+
+    let mut key = 0_u64;
+    for b in (0..HILBERT_BITS).rev() {
+        for x in &axes {
+            key = (key << 1) | ((x >> b) & 1) as u64;
+        }
+    }
+    key
+}
+
+// J. Skilling, AIP Conf. Proc. 707, 381 (2004)
+fn axes_to_transpose(x: &mut [u32; 3], bits: u32) {
+    let m = 1_u32 << (bits - 1);
+
+    // inverse undo
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..3 {
+            if x[i] & q != 0 {
+                x[0] ^= p;
+            } else {
+                let t = (x[0] ^ x[i]) & p;
+                x[0] ^= t;
+                x[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // Gray encode
+    for i in 1..3 {
+        x[i] ^= x[i - 1];
+    }
+    let mut t = 0;
+    q = m;
+    while q > 1 {
+        if x[2] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+    for xi in x.iter_mut() {
+        *xi ^= t;
+    }
+}
+
+#[test]
+fn test_hilbert_curve() {
+    // consecutive points along the curve through a 8x8x8 grid are neighbors
+    let mut points = Vec::new();
+    for i in 0..8 {
+        for j in 0..8 {
+            for k in 0..8 {
+                let mut axes = [i, j, k];
+                axes_to_transpose(&mut axes, 3);
+                let mut key = 0;
+                for b in (0..3).rev() {
+                    for x in &axes {
+                        key = (key << 1) | ((x >> b) & 1);
+                    }
+                }
+                points.push((key, (i as i32, j as i32, k as i32)));
+            }
+        }
+    }
+    points.sort();
+    for (n, w) in points.windows(2).enumerate() {
+        assert_eq!(w[0].0, n as u32);
+        let (a, b) = (w[0].1, w[1].1);
+        assert_eq!((a.0 - b.0).abs() + (a.1 - b.1).abs() + (a.2 - b.2).abs(), 1);
+    }
+}
@@ -1,4 +1,9 @@
-use crate::bragg;
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::neighbors::CellList;
 use crate::parameters;
 
 #[cfg(test)]
@@ -45,6 +50,102 @@ fn test_f3() {
     ));
 }
 
+// f3 and its derivative with respect to x
+#[inline]
+fn f3_with_derivative(x: f64, hardness: usize) -> (f64, f64) {
+    let mut f = x;
+    let mut df = 1.0;
+
+    for _ in 0..hardness {
+        df *= 1.5 - 1.5 * f * f;
+        f *= 1.5 - 0.5 * f * f;
+    }
+
+    (f, df)
+}
+
+#[test]
+fn test_f3_with_derivative() {
+    let step = 1.0e-6;
+    for &x in &[-0.974945, -0.491055, -0.055317, 0.644797, 0.878986] {
+        let (f, df) = f3_with_derivative(x, 3);
+        let df_numerical = (f3(x + step, 3) - f3(x - step, 3)) / (2.0 * step);
+        assert!(comparison::floats_are_same(f, f3(x, 3), 1.0e-15));
+        assert!((df - df_numerical).abs() < 1.0e-8);
+    }
+}
+
+/// Scheme used to partition space into atomic cells.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Partitioning {
+    /// Becke, JCP 88, 2547 (1988), with Bragg radius size adjustments.
+    Becke,
+    /// Stratmann, Scuseria, Frisch, CPL 257, 213 (1996).
+    Ssf,
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for Partitioning {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        match name.to_lowercase().as_str() {
+            "becke" => Ok(Partitioning::Becke),
+            "ssf" => Ok(Partitioning::Ssf),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown partitioning '{}', allowed are: 'becke', 'ssf'",
+                name
+            ))),
+        }
+    }
+}
+
+// CPL 257, 213 (1996), eq. 14
+const SSF_A: f64 = 0.64;
+
+// CPL 257, 213 (1996), eq. 14
+#[inline]
+fn ssf_g(mu: f64) -> f64 {
+    if mu <= -SSF_A {
+        return -1.0;
+    }
+    if mu >= SSF_A {
+        return 1.0;
+    }
+
+    let x = mu / SSF_A;
+    let x2 = x * x;
+
+    0.0625 * x * (35.0 + x2 * (-35.0 + x2 * (21.0 - 5.0 * x2)))
+}
+
+#[test]
+fn test_ssf_g() {
+    assert!(comparison::floats_are_same(ssf_g(-0.64), -1.0, 1.0e-15));
+    assert!(comparison::floats_are_same(ssf_g(-0.9), -1.0, 1.0e-15));
+    assert!(comparison::floats_are_same(ssf_g(0.0), 0.0, 1.0e-15));
+    assert!(comparison::floats_are_same(ssf_g(0.64), 1.0, 1.0e-15));
+    assert!(comparison::floats_are_same(
+        ssf_g(0.32),
+        0.85888671875,
+        1.0e-15
+    ));
+    assert!(comparison::floats_are_same(
+        ssf_g(-0.16),
+        -0.5139579772949219,
+        1.0e-15
+    ));
+}
+
+// (p1 - p2) / |p1 - p2|
+fn unit_vector(p1: &(f64, f64, f64), p2: &(f64, f64, f64)) -> [f64; 3] {
+    let d = distance(p1, p2);
+    if d > 0.0 {
+        [(p1.0 - p2.0) / d, (p1.1 - p2.1) / d, (p1.2 - p2.2) / d]
+    } else {
+        [0.0; 3]
+    }
+}
+
 fn distance(p1: &(f64, f64, f64), p2: &(f64, f64, f64)) -> f64 {
     let dx = p1.0 - p2.0;
     let dy = p1.1 - p2.1;
@@ -53,27 +154,49 @@ fn distance(p1: &(f64, f64, f64), p2: &(f64, f64, f64)) -> f64 {
     (dx * dx + dy * dy + dz * dz).sqrt()
 }
 
-// JCP 88, 2547 (1988)
 pub fn partitioning_weight(
+    partitioning: Partitioning,
     center_index: usize,
     center_coordinates_bohr: &[(f64, f64, f64)],
-    proton_charges: &[i32],
+    atomic_radii: &[f64],
     grid_coordinates_bohr: (f64, f64, f64),
     hardness: usize,
 ) -> f64 {
-    let num_centers = proton_charges.len();
+    match partitioning {
+        Partitioning::Becke => becke_weight(
+            center_index,
+            center_coordinates_bohr,
+            atomic_radii,
+            grid_coordinates_bohr,
+            hardness,
+        ),
+        Partitioning::Ssf => {
+            ssf_weight(center_index, center_coordinates_bohr, grid_coordinates_bohr)
+        }
+    }
+}
+
+// JCP 88, 2547 (1988)
+fn becke_weight(
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    atomic_radii: &[f64],
+    grid_coordinates_bohr: (f64, f64, f64),
+    hardness: usize,
+) -> f64 {
+    let num_centers = center_coordinates_bohr.len();
 
     let mut pa = vec![1.0; num_centers];
 
     for ia in 0..num_centers {
         let dist_a = distance(&grid_coordinates_bohr, &center_coordinates_bohr[ia]);
 
-        let r_a = bragg::get_bragg_angstrom(proton_charges[ia]);
+        let r_a = atomic_radii[ia];
 
         for ib in 0..ia {
             let dist_b = distance(&grid_coordinates_bohr, &center_coordinates_bohr[ib]);
 
-            let r_b = bragg::get_bragg_angstrom(proton_charges[ib]);
+            let r_b = atomic_radii[ib];
 
             let dist_ab = distance(&center_coordinates_bohr[ia], &center_coordinates_bohr[ib]);
 
@@ -85,7 +208,7 @@ pub fn partitioning_weight(
                 let u_ab = (r_a + r_b) / (r_b - r_a);
                 let a_ab = u_ab / (u_ab * u_ab - 1.0);
 
-                nu_ab += a_ab.min(0.5).max(-0.5) * (1.0 - mu_ab * mu_ab);
+                nu_ab += a_ab.clamp(-0.5, 0.5) * (1.0 - mu_ab * mu_ab);
             }
 
             let f = f3(nu_ab, hardness);
@@ -108,3 +231,432 @@ pub fn partitioning_weight(
         1.0
     }
 }
+
+/// Becke partitioning weight and its derivatives with respect to the
+/// coordinates of all centers, for a grid point which moves rigidly with the
+/// center `center_index`.
+pub fn partitioning_weight_gradient(
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    atomic_radii: &[f64],
+    grid_coordinates_bohr: (f64, f64, f64),
+    hardness: usize,
+) -> (f64, Vec<(f64, f64, f64)>) {
+    let num_centers = center_coordinates_bohr.len();
+
+    let mut pa = vec![1.0; num_centers];
+
+    // d ln(P_a) / d R_k is stored at index a * num_centers + k
+    let mut dlnpa = vec![[0.0; 3]; num_centers * num_centers];
+
+    for ia in 0..num_centers {
+        let dist_a = distance(&grid_coordinates_bohr, &center_coordinates_bohr[ia]);
+        let e_a = unit_vector(&grid_coordinates_bohr, &center_coordinates_bohr[ia]);
+
+        let r_a = atomic_radii[ia];
+
+        for ib in 0..ia {
+            let dist_b = distance(&grid_coordinates_bohr, &center_coordinates_bohr[ib]);
+            let e_b = unit_vector(&grid_coordinates_bohr, &center_coordinates_bohr[ib]);
+
+            let r_b = atomic_radii[ib];
+
+            let dist_ab = distance(&center_coordinates_bohr[ia], &center_coordinates_bohr[ib]);
+            let e_ab = unit_vector(&center_coordinates_bohr[ia], &center_coordinates_bohr[ib]);
+
+            // JCP 88, 2547 (1988), eq. 11
+            let mu_ab = (dist_a - dist_b) / dist_ab;
+
+            let mut a_ab = 0.0;
+            if (r_a - r_b).abs() > parameters::SMALL {
+                let u_ab = (r_a + r_b) / (r_b - r_a);
+                a_ab = (u_ab / (u_ab * u_ab - 1.0)).clamp(-0.5, 0.5);
+            }
+            let nu_ab = mu_ab + a_ab * (1.0 - mu_ab * mu_ab);
+
+            let (f, df) = f3_with_derivative(nu_ab, hardness);
+            let df_dmu = df * (1.0 - 2.0 * a_ab * mu_ab);
+
+            if (1.0 - f).abs() > parameters::SMALL {
+                let s_a = 0.5 * (1.0 - f);
+                let s_b = 0.5 * (1.0 + f);
+                pa[ia] *= s_a;
+                pa[ib] *= s_b;
+
+                // d mu_ab / d R_k is nonzero only for k = center_index, ia, ib
+                let dmu = [
+                    (
+                        center_index,
+                        [e_a[0] - e_b[0], e_a[1] - e_b[1], e_a[2] - e_b[2]],
+                    ),
+                    (
+                        ia,
+                        [
+                            -e_a[0] - mu_ab * e_ab[0],
+                            -e_a[1] - mu_ab * e_ab[1],
+                            -e_a[2] - mu_ab * e_ab[2],
+                        ],
+                    ),
+                    (
+                        ib,
+                        [
+                            e_b[0] + mu_ab * e_ab[0],
+                            e_b[1] + mu_ab * e_ab[1],
+                            e_b[2] + mu_ab * e_ab[2],
+                        ],
+                    ),
+                ];
+
+                for (k, g) in dmu.iter() {
+                    for ixyz in 0..3 {
+                        let dmu_k = g[ixyz] / dist_ab;
+                        dlnpa[ia * num_centers + k][ixyz] -= 0.5 * df_dmu * dmu_k / s_a;
+                        if s_b > 0.0 {
+                            dlnpa[ib * num_centers + k][ixyz] += 0.5 * df_dmu * dmu_k / s_b;
+                        }
+                    }
+                }
+            } else {
+                // avoid numerical issues
+                pa[ia] = 0.0;
+            }
+        }
+    }
+
+    let w: f64 = pa.iter().sum();
+
+    if w.abs() <= parameters::SMALL {
+        return (1.0, vec![(0.0, 0.0, 0.0); num_centers]);
+    }
+
+    let weight = pa[center_index] / w;
+
+    let gradient = (0..num_centers)
+        .map(|k| {
+            let mut g = [0.0; 3];
+            for (ia, &p) in pa.iter().enumerate() {
+                if p == 0.0 {
+                    continue;
+                }
+                let dlnp = &dlnpa[ia * num_centers + k];
+                let factor = if ia == center_index {
+                    1.0 - weight
+                } else {
+                    -weight
+                };
+                for ixyz in 0..3 {
+                    g[ixyz] += factor * p * dlnp[ixyz] / w;
+                }
+            }
+            (g[0], g[1], g[2])
+        })
+        .collect();
+
+    (weight, gradient)
+}
+
+// CPL 257, 213 (1996)
+fn ssf_weight(
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    grid_coordinates_bohr: (f64, f64, f64),
+) -> f64 {
+    let num_centers = center_coordinates_bohr.len();
+    let center = &center_coordinates_bohr[center_index];
+
+    let dist: Vec<f64> = center_coordinates_bohr
+        .iter()
+        .map(|c| distance(&grid_coordinates_bohr, c))
+        .collect();
+
+    // the point lies entirely inside the cell of its own center
+    // if it is closer than 0.5 * (1 - a) times the nearest-neighbor distance
+    let nearest_neighbor = (0..num_centers)
+        .filter(|&ib| ib != center_index)
+        .map(|ib| distance(center, &center_coordinates_bohr[ib]))
+        .fold(f64::MAX, f64::min);
+    if dist[center_index] <= 0.5 * (1.0 - SSF_A) * nearest_neighbor {
+        return 1.0;
+    }
+
+    let cell_function = |ia: usize| ssf_cell_function(ia, center_coordinates_bohr, &dist);
+
+    let p_center = cell_function(center_index);
+    if p_center == 0.0 {
+        return 0.0;
+    }
+
+    let w: f64 = (0..num_centers)
+        .map(|ia| {
+            if ia == center_index {
+                p_center
+            } else {
+                cell_function(ia)
+            }
+        })
+        .sum();
+
+    p_center / w
+}
+
+// CPL 257, 213 (1996), eq. 8 with the cutoff profile of eq. 14
+fn ssf_cell_function(ia: usize, center_coordinates_bohr: &[(f64, f64, f64)], dist: &[f64]) -> f64 {
+    let mut p = 1.0;
+    for ib in 0..center_coordinates_bohr.len() {
+        if ib == ia {
+            continue;
+        }
+        let dist_ab = distance(&center_coordinates_bohr[ia], &center_coordinates_bohr[ib]);
+        let mu_ab = (dist[ia] - dist[ib]) / dist_ab;
+        p *= 0.5 * (1.0 - ssf_g(mu_ab));
+        if p == 0.0 {
+            break;
+        }
+    }
+    p
+}
+
+// s(nu_ab) of JCP 88, 2547 (1988), eq. 21 with the size adjustment of eq. A2
+fn becke_cell_factor(
+    ia: usize,
+    ib: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    atomic_radii: &[f64],
+    dist: &[f64],
+    hardness: usize,
+) -> f64 {
+    let dist_ab = distance(&center_coordinates_bohr[ia], &center_coordinates_bohr[ib]);
+    let mu_ab = (dist[ia] - dist[ib]) / dist_ab;
+
+    let r_a = atomic_radii[ia];
+    let r_b = atomic_radii[ib];
+
+    let mut nu_ab = mu_ab;
+    if (r_a - r_b).abs() > parameters::SMALL {
+        let u_ab = (r_a + r_b) / (r_b - r_a);
+        let a_ab = u_ab / (u_ab * u_ab - 1.0);
+
+        nu_ab += a_ab.clamp(-0.5, 0.5) * (1.0 - mu_ab * mu_ab);
+    }
+
+    0.5 * (1.0 - f3(nu_ab, hardness))
+}
+
+// the SSF cell function of C is zero if another center is closer than r_C / K,
+// and a center B does not change the cell function of C if r_B >= K r_C,
+// with K = (1 + a) / (1 - a), since then mu_CB <= -a
+const SSF_SCREENING_FACTOR: f64 = (1.0 + SSF_A) / (1.0 - SSF_A);
+
+// Becke cell functions never become exactly one; a center B with r_B >= 300 r_A
+// has mu_AB <= -299/301 and changes the cell function of A by a relative 1e-14
+// at most (with the strongest size adjustment); summed over all left-out
+// centers the weights of a cluster of 1029 atoms deviate by less than 1.0e-10
+// from the full calculation (tested in becke_screening_error)
+const BECKE_SCREENING_FACTOR: f64 = 300.0;
+
+// Becke cell functions below this bound are set to zero
+const BECKE_CELL_THRESHOLD: f64 = parameters::SMALL;
+
+/// Like `partitioning_weight` but only centers near the grid point enter the
+/// products of cell functions, and only centers whose cell function does not
+/// vanish enter the normalization. For SSF the weights are the same as
+/// without screening, for Becke they deviate by up to about 1.0e-10.
+pub fn screened_partitioning_weight(
+    partitioning: Partitioning,
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    atomic_radii: &[f64],
+    cell_list: &CellList,
+    grid_coordinates_bohr: (f64, f64, f64),
+    hardness: usize,
+) -> f64 {
+    let dist_center = distance(
+        &grid_coordinates_bohr,
+        &center_coordinates_bohr[center_index],
+    );
+
+    // the nearest center is not farther away than the own center
+    let mut indices = Vec::new();
+    cell_list.within(grid_coordinates_bohr, dist_center, &mut indices);
+    let (nearest, dist_nearest) = indices
+        .iter()
+        .map(|&i| {
+            (
+                i,
+                distance(&grid_coordinates_bohr, &center_coordinates_bohr[i]),
+            )
+        })
+        .fold(
+            (center_index, dist_center),
+            |a, b| if b.1 < a.1 { b } else { a },
+        );
+
+    let radius = match partitioning {
+        Partitioning::Becke => BECKE_SCREENING_FACTOR * dist_nearest,
+        Partitioning::Ssf => SSF_SCREENING_FACTOR * dist_nearest,
+    };
+    cell_list.within(grid_coordinates_bohr, radius, &mut indices);
+    let mut local = LocalCenters::new(
+        &indices,
+        center_coordinates_bohr,
+        atomic_radii,
+        grid_coordinates_bohr,
+    );
+    let Some(local_nearest) = local.position(nearest) else {
+        return partitioning_weight(
+            partitioning,
+            center_index,
+            center_coordinates_bohr,
+            atomic_radii,
+            grid_coordinates_bohr,
+            hardness,
+        );
+    };
+
+    let cells: Vec<usize> = match partitioning {
+        Partitioning::Becke => (0..local.len()).collect(),
+        Partitioning::Ssf => {
+            // centers B with r_B < r_C are all within K r_nearest, so a
+            // vanishing cell function is already visible with these centers;
+            // the factor with the nearest center alone rules out most of them
+            let cells: Vec<usize> = (0..local.len())
+                .filter(|&ic| {
+                    ic == local_nearest || {
+                        let dist_cn =
+                            distance(&local.coordinates[ic], &local.coordinates[local_nearest]);
+                        (local.dist[ic] - local.dist[local_nearest]) / dist_cn < SSF_A
+                    }
+                })
+                .filter(|&ic| ssf_cell_function(ic, &local.coordinates, &local.dist) > 0.0)
+                .collect();
+            let dist_max = cells.iter().map(|&ic| local.dist[ic]).fold(0.0, f64::max);
+            let cell_indices: Vec<usize> = cells.iter().map(|&ic| indices[ic]).collect();
+
+            let mut indices = Vec::new();
+            cell_list.within(
+                grid_coordinates_bohr,
+                SSF_SCREENING_FACTOR * dist_max,
+                &mut indices,
+            );
+            local = LocalCenters::new(
+                &indices,
+                center_coordinates_bohr,
+                atomic_radii,
+                grid_coordinates_bohr,
+            );
+            let Some(cells) = cell_indices
+                .iter()
+                .map(|&i| local.position(i))
+                .collect::<Option<Vec<usize>>>()
+            else {
+                return partitioning_weight(
+                    partitioning,
+                    center_index,
+                    center_coordinates_bohr,
+                    atomic_radii,
+                    grid_coordinates_bohr,
+                    hardness,
+                );
+            };
+            cells
+        }
+    };
+
+    let Some(local_center) = local.position(center_index) else {
+        return 0.0;
+    };
+    if !cells.contains(&local_center) {
+        return 0.0;
+    }
+
+    // nearest centers first so that small cell functions are found early
+    let mut order: Vec<usize> = (0..local.len()).collect();
+    if partitioning == Partitioning::Becke {
+        order.sort_by(|&i, &j| local.dist[i].total_cmp(&local.dist[j]));
+    }
+
+    let cell_function = |ia: usize| match partitioning {
+        Partitioning::Becke => {
+            let mut p = 1.0;
+            for &ib in &order {
+                if ib == ia {
+                    continue;
+                }
+                p *= becke_cell_factor(
+                    ia,
+                    ib,
+                    &local.coordinates,
+                    &local.radii,
+                    &local.dist,
+                    hardness,
+                );
+                if p < BECKE_CELL_THRESHOLD {
+                    return 0.0;
+                }
+            }
+            p
+        }
+        Partitioning::Ssf => ssf_cell_function(ia, &local.coordinates, &local.dist),
+    };
+
+    let p_center = cell_function(local_center);
+    if p_center == 0.0 {
+        return 0.0;
+    }
+
+    let w: f64 = cells
+        .iter()
+        .map(|&ia| {
+            if ia == local_center {
+                p_center
+            } else {
+                cell_function(ia)
+            }
+        })
+        .sum();
+
+    if partitioning == Partitioning::Becke && w.abs() <= parameters::SMALL {
+        return 1.0;
+    }
+
+    p_center / w
+}
+
+// centers near a grid point, in ascending order of their global index
+struct LocalCenters {
+    indices: Vec<usize>,
+    coordinates: Vec<(f64, f64, f64)>,
+    radii: Vec<f64>,
+    dist: Vec<f64>,
+}
+
+impl LocalCenters {
+    fn new(
+        indices: &[usize],
+        center_coordinates_bohr: &[(f64, f64, f64)],
+        atomic_radii: &[f64],
+        grid_coordinates_bohr: (f64, f64, f64),
+    ) -> Self {
+        let coordinates: Vec<(f64, f64, f64)> = indices
+            .iter()
+            .map(|&i| center_coordinates_bohr[i])
+            .collect();
+        LocalCenters {
+            indices: indices.to_vec(),
+            radii: indices.iter().map(|&i| atomic_radii[i]).collect(),
+            dist: coordinates
+                .iter()
+                .map(|c| distance(&grid_coordinates_bohr, c))
+                .collect(),
+            coordinates,
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    fn position(&self, index: usize) -> Option<usize> {
+        self.indices.binary_search(&index).ok()
+    }
+}
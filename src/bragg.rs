@@ -1,6 +1,8 @@
+use crate::error::NumgridError;
+
 // we use the same values as the DIRAC15 code
-pub fn get_bragg_angstrom(charge: i32) -> f64 {
-    match charge {
+pub fn get_bragg_angstrom(charge: i32) -> Result<f64, NumgridError> {
+    let radius = match charge {
         1 => 0.35,
         2 => 0.35,
         3 => 1.45,
@@ -87,6 +89,35 @@ pub fn get_bragg_angstrom(charge: i32) -> f64 {
         84 => 1.90,
         85 => 1.50,
         86 => 1.50,
-        _ => panic!("Bragg radius not found for input charge"),
+        // Slater, JCP 41, 3199 (1964); Fr is missing there and we use Cs
+        87 => 2.60,
+        88 => 2.15,
+        89 => 1.95,
+        90 => 1.80,
+        91 => 1.80,
+        92 => 1.75,
+        93 => 1.75,
+        94 => 1.75,
+        95 => 1.75,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+
+    Ok(radius)
+}
+
+#[test]
+fn test_get_bragg_angstrom() {
+    for charge in 1..=95 {
+        assert!(get_bragg_angstrom(charge).is_ok());
+    }
+    assert_eq!(get_bragg_angstrom(92).unwrap(), 1.75);
+    assert_eq!(get_bragg_angstrom(95).unwrap(), 1.75);
+    assert!(get_bragg_angstrom(0).is_err());
+    // no published values beyond Am
+    for charge in [96, 103, 118] {
+        assert!(matches!(
+            get_bragg_angstrom(charge),
+            Err(NumgridError::UnsupportedElement(c)) if c == charge
+        ));
     }
 }
@@ -1,6 +1,16 @@
+#[cfg(feature = "bse-online")]
 use reqwest::header::{HeaderMap, HeaderValue, USER_AGENT};
 use serde::Deserialize;
 use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::NumgridError;
+
+// directory with basis sets downloaded from https://www.basissetexchange.org
+// in JSON format, searched before falling back to the REST API
+const BSE_DIR_VARIABLE: &str = "NUMGRID_BSE_DIR";
 
 #[derive(Deserialize, Debug)]
 struct Basis {
@@ -18,7 +28,102 @@ struct Shell {
     exponents: Vec<String>,
 }
 
-pub fn ang_min_and_max(basis_set: &str, element: usize) -> (HashMap<usize, f64>, f64) {
+// basis_set is either a path to a BSE JSON file, or the name of a basis set
+// which is then looked up in $NUMGRID_BSE_DIR (if set) or fetched from the
+// BSE REST API
+pub fn ang_min_and_max(
+    basis_set: &str,
+    element: usize,
+) -> Result<(HashMap<usize, f64>, f64), NumgridError> {
+    let resp = read_basis(basis_set, element)?;
+
+    let element = match resp.elements.get(&element.to_string()) {
+        Some(v) => v,
+        None => {
+            return Err(NumgridError::NotFound(format!(
+                "element {} in basis set {}",
+                element, basis_set
+            )))
+        }
+    };
+
+    let mut alpha_max = f64::MIN;
+    let mut alpha_min = HashMap::new();
+
+    for shell in &element.electron_shells {
+        // SP shells list both angular momenta and share the exponents
+        if shell.angular_momentum.is_empty() {
+            return Err(NumgridError::Parse(format!(
+                "shell without angular momentum in basis set {}",
+                basis_set
+            )));
+        }
+        for exponent_string in &shell.exponents {
+            let exponent: f64 = match exponent_string.parse() {
+                Ok(v) => v,
+                Err(_) => {
+                    return Err(NumgridError::Parse(format!(
+                        "invalid exponent {} in basis set {}",
+                        exponent_string, basis_set
+                    )))
+                }
+            };
+            alpha_max = alpha_max.max(exponent);
+            for &angular_momentum in &shell.angular_momentum {
+                let s = alpha_min.entry(angular_momentum).or_insert(f64::MAX);
+                if &exponent < s {
+                    *s = exponent;
+                }
+            }
+        }
+    }
+
+    Ok((alpha_min, alpha_max))
+}
+
+fn read_basis(basis_set: &str, element: usize) -> Result<Basis, NumgridError> {
+    let path = Path::new(basis_set);
+    if path.is_file() {
+        return read_basis_file(path);
+    }
+
+    if let Ok(directory) = env::var(BSE_DIR_VARIABLE) {
+        return match find_basis_file(Path::new(&directory), basis_set) {
+            Some(file_name) => read_basis_file(&file_name),
+            None => Err(NumgridError::NotFound(format!(
+                "basis set {} in {}={}",
+                basis_set, BSE_DIR_VARIABLE, directory
+            ))),
+        };
+    }
+
+    fetch_basis(basis_set, element)
+}
+
+fn read_basis_file(file_name: &Path) -> Result<Basis, NumgridError> {
+    let contents = match fs::read_to_string(file_name) {
+        Ok(v) => v,
+        Err(e) => {
+            return Err(NumgridError::Io(format!(
+                "could not read {}: {}",
+                file_name.display(),
+                e
+            )))
+        }
+    };
+
+    match serde_json::from_str(&contents) {
+        Ok(v) => Ok(v),
+        Err(e) => Err(NumgridError::Parse(format!(
+            "could not parse {}: {}",
+            file_name.display(),
+            e
+        ))),
+    }
+}
+
+#[cfg(feature = "bse-online")]
+fn fetch_basis(basis_set: &str, element: usize) -> Result<Basis, NumgridError> {
     let mut headers = HeaderMap::new();
     headers.insert(USER_AGENT, HeaderValue::from_static("reqwest"));
 
@@ -27,30 +132,110 @@ pub fn ang_min_and_max(basis_set: &str, element: usize) -> (HashMap<usize, f64>,
         basis_set, element,
     );
 
-    let resp = reqwest::blocking::Client::new()
+    let basis = reqwest::blocking::Client::new()
         .get(url)
         .headers(headers)
-        .send()
-        .unwrap()
-        .json::<Basis>()
-        .unwrap();
+        .send()?
+        .error_for_status()?
+        .json::<Basis>()?;
 
-    let element = resp.elements.get(&element.to_string()).unwrap();
+    Ok(basis)
+}
 
-    let mut alpha_max = -std::f64::MAX;
-    let mut alpha_min = HashMap::new();
+#[cfg(not(feature = "bse-online"))]
+fn fetch_basis(basis_set: &str, _element: usize) -> Result<Basis, NumgridError> {
+    Err(NumgridError::NotFound(format!(
+        "basis set {} is not a file and {} is not set; fetching from the Basis Set Exchange needs the bse-online feature",
+        basis_set, BSE_DIR_VARIABLE
+    )))
+}
 
-    for shell in &element.electron_shells {
-        let angular_momentum = shell.angular_momentum[0];
-        for exponent_string in &shell.exponents {
-            let exponent: f64 = exponent_string.parse().unwrap();
-            alpha_max = alpha_max.max(exponent);
-            let s = alpha_min.entry(angular_momentum).or_insert(std::f64::MAX);
-            if &exponent < s {
-                *s = exponent;
+// BSE names its files <name>.<version>.json, with the name lowercased and
+// "/" and "*" replaced; if there are several versions we take the latest
+fn find_basis_file(directory: &Path, basis_set: &str) -> Option<PathBuf> {
+    let name = basis_set
+        .to_lowercase()
+        .replace('/', "_sl_")
+        .replace('*', "_st_");
+
+    let mut best: Option<(u32, PathBuf)> = None;
+
+    for entry in fs::read_dir(directory).ok()?.flatten() {
+        let file_name = entry.file_name().to_string_lossy().to_lowercase();
+
+        let stem = match file_name.strip_suffix(".json") {
+            Some(v) => v,
+            None => continue,
+        };
+
+        let version = if stem == name {
+            0
+        } else {
+            match stem
+                .strip_prefix(&name)
+                .and_then(|v| v.strip_prefix('.'))
+                .and_then(|v| v.parse::<u32>().ok())
+            {
+                Some(v) => v,
+                None => continue,
             }
+        };
+
+        if best.as_ref().is_none_or(|(v, _)| version > *v) {
+            best = Some((version, entry.path()));
         }
     }
 
-    (alpha_min, alpha_max)
+    best.map(|(_, path)| path)
+}
+
+#[test]
+fn test_find_basis_file() {
+    let directory = Path::new("tests/basis");
+    assert_eq!(
+        find_basis_file(directory, "cc-pVDZ"),
+        Some(directory.join("cc-pvdz.1.json"))
+    );
+    assert_eq!(find_basis_file(directory, "cc-pVTZ"), None);
+    assert_eq!(find_basis_file(directory, "cc-pV"), None);
+}
+
+#[test]
+fn test_ang_min_and_max_from_file() {
+    let (alpha_min, alpha_max) = ang_min_and_max("tests/basis/cc-pvdz.1.json", 8).unwrap();
+    assert_eq!(alpha_max, 11720.0);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.3023);
+    assert_eq!(alpha_min[&1], 0.2753);
+    assert_eq!(alpha_min[&2], 1.185);
+
+    let (alpha_min, alpha_max) = ang_min_and_max("tests/basis/cc-pvdz.1.json", 1).unwrap();
+    assert_eq!(alpha_max, 13.01);
+    assert_eq!(alpha_min.len(), 2);
+    assert_eq!(alpha_min[&0], 0.122);
+    assert_eq!(alpha_min[&1], 0.727);
+
+    assert!(ang_min_and_max("tests/basis/cc-pvdz.1.json", 6).is_err());
+}
+
+#[test]
+fn test_ang_min_and_max_sp_shells() {
+    // the SP shells of 6-31G* give the smallest s and p exponents
+    let (alpha_min, alpha_max) = ang_min_and_max("tests/basis/6-31g_st_.0.json", 8).unwrap();
+    assert_eq!(alpha_max, 5484.67166);
+    assert_eq!(alpha_min.len(), 3);
+    assert_eq!(alpha_min[&0], 0.2700058226);
+    assert_eq!(alpha_min[&1], 0.2700058226);
+    assert_eq!(alpha_min[&2], 0.8);
+}
+
+#[cfg(not(feature = "bse-online"))]
+#[test]
+fn test_no_online_lookup() {
+    if env::var(BSE_DIR_VARIABLE).is_err() {
+        assert!(matches!(
+            ang_min_and_max("cc-pVDZ", 8),
+            Err(NumgridError::NotFound(_))
+        ));
+    }
 }
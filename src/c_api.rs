@@ -0,0 +1,523 @@
+// C interface, declared in include/numgrid.h which also documents the
+// arguments. Output buffers are allocated by the caller. All functions return
+// an error code and never unwind into the caller.
+
+#![allow(clippy::too_many_arguments, clippy::missing_safety_doc)]
+
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::ffi::{c_char, c_int};
+use std::panic::{self, AssertUnwindSafe};
+use std::slice;
+
+use crate::atom::AtomGridBuilder;
+use crate::becke_partitioning::Partitioning;
+use crate::error::NumgridError;
+use crate::lebedev;
+use crate::molecule;
+use crate::molecule::MolecularGridBuilder;
+use crate::radial;
+use crate::radial::RadialScheme;
+
+// keep in sync with include/numgrid.h
+const NUMGRID_SUCCESS: c_int = 0;
+const NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID: c_int = 1;
+const NUMGRID_ERROR_UNSUPPORTED_ELEMENT: c_int = 2;
+const NUMGRID_ERROR_INVALID_INPUT: c_int = 3;
+const NUMGRID_ERROR_NOT_FOUND: c_int = 4;
+const NUMGRID_ERROR_IO: c_int = 5;
+const NUMGRID_ERROR_PARSE: c_int = 6;
+const NUMGRID_ERROR_NETWORK: c_int = 7;
+const NUMGRID_ERROR_BUFFER_TOO_SMALL: c_int = 8;
+const NUMGRID_ERROR_INTERNAL: c_int = 9;
+
+const NUMGRID_PARTITIONING_BECKE: c_int = 0;
+const NUMGRID_PARTITIONING_SSF: c_int = 1;
+
+thread_local! {
+    static LAST_ERROR_MESSAGE: RefCell<String> = const { RefCell::new(String::new()) };
+}
+
+enum CError {
+    Numgrid(NumgridError),
+    BufferTooSmall { capacity: usize, num_points: usize },
+}
+
+impl From<NumgridError> for CError {
+    fn from(e: NumgridError) -> Self {
+        CError::Numgrid(e)
+    }
+}
+
+// runs the function and translates errors and panics into error codes,
+// the message is kept for numgrid_last_error_message
+fn guard(f: impl FnOnce() -> Result<(), CError>) -> c_int {
+    let (code, message) = match panic::catch_unwind(AssertUnwindSafe(f)) {
+        Ok(Ok(())) => return NUMGRID_SUCCESS,
+        Ok(Err(CError::Numgrid(e))) => {
+            let code = match e {
+                NumgridError::UnsupportedAngularGrid { .. } => {
+                    NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID
+                }
+                NumgridError::UnsupportedElement(_) => NUMGRID_ERROR_UNSUPPORTED_ELEMENT,
+                NumgridError::InvalidInput(_) => NUMGRID_ERROR_INVALID_INPUT,
+                NumgridError::NotFound(_) => NUMGRID_ERROR_NOT_FOUND,
+                NumgridError::Io(_) => NUMGRID_ERROR_IO,
+                NumgridError::Parse(_) => NUMGRID_ERROR_PARSE,
+                NumgridError::Network(_) => NUMGRID_ERROR_NETWORK,
+            };
+            (code, e.to_string())
+        }
+        Ok(Err(CError::BufferTooSmall {
+            capacity,
+            num_points,
+        })) => (
+            NUMGRID_ERROR_BUFFER_TOO_SMALL,
+            format!(
+                "buffer too small: capacity {} but {} points",
+                capacity, num_points
+            ),
+        ),
+        Err(_) => (NUMGRID_ERROR_INTERNAL, "internal error".to_string()),
+    };
+    LAST_ERROR_MESSAGE.with(|m| *m.borrow_mut() = message);
+    code
+}
+
+unsafe fn input<'a, T>(pointer: *const T, length: usize) -> Result<&'a [T], NumgridError> {
+    if length == 0 {
+        return Ok(&[]);
+    }
+    if pointer.is_null() {
+        return Err(NumgridError::InvalidInput("null pointer".to_string()));
+    }
+    Ok(slice::from_raw_parts(pointer, length))
+}
+
+unsafe fn output<'a, T>(pointer: *mut T, length: usize) -> Result<&'a mut [T], NumgridError> {
+    if length == 0 {
+        return Ok(&mut []);
+    }
+    if pointer.is_null() {
+        return Err(NumgridError::InvalidInput("null pointer".to_string()));
+    }
+    Ok(slice::from_raw_parts_mut(pointer, length))
+}
+
+unsafe fn set_num_points(num_points: *mut usize, n: usize) -> Result<(), NumgridError> {
+    output(num_points, 1)?[0] = n;
+    Ok(())
+}
+
+fn check_capacity(capacity: usize, num_points: usize) -> Result<(), CError> {
+    if capacity < num_points {
+        return Err(CError::BufferTooSmall {
+            capacity,
+            num_points,
+        });
+    }
+    Ok(())
+}
+
+unsafe fn center_coordinates(
+    num_centers: usize,
+    x: *const f64,
+    y: *const f64,
+    z: *const f64,
+) -> Result<Vec<(f64, f64, f64)>, NumgridError> {
+    let (x, y, z) = (
+        input(x, num_centers)?,
+        input(y, num_centers)?,
+        input(z, num_centers)?,
+    );
+    Ok((0..num_centers).map(|i| (x[i], y[i], z[i])).collect())
+}
+
+// smallest exponent for each angular momentum starting from s,
+// entries which are not positive are skipped
+fn alpha_min_map(alpha_min: &[f64]) -> HashMap<usize, f64> {
+    alpha_min
+        .iter()
+        .enumerate()
+        .filter(|(_, &alpha)| alpha > 0.0)
+        .map(|(l, &alpha)| (l, alpha))
+        .collect()
+}
+
+fn partitioning(partitioning: c_int) -> Result<Partitioning, NumgridError> {
+    match partitioning {
+        NUMGRID_PARTITIONING_BECKE => Ok(Partitioning::Becke),
+        NUMGRID_PARTITIONING_SSF => Ok(Partitioning::Ssf),
+        _ => Err(NumgridError::InvalidInput(format!(
+            "unknown partitioning {}",
+            partitioning
+        ))),
+    }
+}
+
+unsafe fn write_grid(
+    coordinates: &[(f64, f64, f64)],
+    weights: &[f64],
+    x: *mut f64,
+    y: *mut f64,
+    z: *mut f64,
+    w: *mut f64,
+) -> Result<(), NumgridError> {
+    let n = weights.len();
+    let (x, y, z, w) = (output(x, n)?, output(y, n)?, output(z, n)?, output(w, n)?);
+    for (i, &(cx, cy, cz)) in coordinates.iter().enumerate() {
+        x[i] = cx;
+        y[i] = cy;
+        z[i] = cz;
+    }
+    w.copy_from_slice(weights);
+    Ok(())
+}
+
+unsafe fn write_radial_grid(
+    (radii, weights): (Vec<f64>, Vec<f64>),
+    r: *mut f64,
+    w: *mut f64,
+) -> Result<(), NumgridError> {
+    output(r, radii.len())?.copy_from_slice(&radii);
+    output(w, weights.len())?.copy_from_slice(&weights);
+    Ok(())
+}
+
+// same grid as atom::atom_grid with default pruning and radii
+fn lmg_builder(
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+) -> AtomGridBuilder {
+    AtomGridBuilder::new(RadialScheme::Lmg {
+        alpha_min,
+        alpha_max,
+        radial_precision,
+    })
+    .angular_points(min_num_angular_points, max_num_angular_points)
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_last_error_message(buffer: *mut c_char, length: usize) -> usize {
+    LAST_ERROR_MESSAGE.with(|m| {
+        let message = m.borrow();
+        if !buffer.is_null() && length > 0 {
+            let n = message.len().min(length - 1);
+            let buffer = slice::from_raw_parts_mut(buffer as *mut u8, length);
+            buffer[..n].copy_from_slice(&message.as_bytes()[..n]);
+            buffer[n] = 0;
+        }
+        message.len()
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_angular_grid(
+    num_points: usize,
+    x: *mut f64,
+    y: *mut f64,
+    z: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        let (coordinates, weights) = lebedev::angular_grid(num_points)?;
+        write_grid(&coordinates, &weights, x, y, z, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_kk(
+    num_points: usize,
+    r: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        write_radial_grid(radial::radial_grid_kk(num_points), r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_ta(
+    num_points: usize,
+    proton_charge: c_int,
+    r: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        write_radial_grid(radial::radial_grid_ta(num_points, proton_charge)?, r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_mk(
+    num_points: usize,
+    proton_charge: c_int,
+    r: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        write_radial_grid(radial::radial_grid_mk(num_points, proton_charge)?, r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_em(
+    num_points: usize,
+    proton_charge: c_int,
+    r: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        write_radial_grid(radial::radial_grid_em(num_points, proton_charge)?, r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_becke(
+    num_points: usize,
+    proton_charge: c_int,
+    r: *mut f64,
+    w: *mut f64,
+) -> c_int {
+    guard(|| {
+        write_radial_grid(radial::radial_grid_becke(num_points, proton_charge)?, r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_radial_grid_lmg(
+    alpha_min: *const f64,
+    num_alpha_min: usize,
+    alpha_max: f64,
+    radial_precision: f64,
+    proton_charge: c_int,
+    capacity: usize,
+    r: *mut f64,
+    w: *mut f64,
+    num_points: *mut usize,
+) -> c_int {
+    guard(|| {
+        let alpha_min = alpha_min_map(input(alpha_min, num_alpha_min)?);
+        let grid = radial::radial_grid_lmg(alpha_min, alpha_max, radial_precision, proton_charge)?;
+        set_num_points(num_points, grid.0.len())?;
+        check_capacity(capacity, grid.0.len())?;
+        write_radial_grid(grid, r, w)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_atom_grid_num_points(
+    alpha_min: *const f64,
+    num_alpha_min: usize,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    num_centers: usize,
+    proton_charges: *const c_int,
+    center_x_bohr: *const f64,
+    center_y_bohr: *const f64,
+    center_z_bohr: *const f64,
+    center_index: usize,
+    num_points: *mut usize,
+) -> c_int {
+    guard(|| {
+        let builder = lmg_builder(
+            alpha_min_map(input(alpha_min, num_alpha_min)?),
+            alpha_max,
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+        );
+        let n = builder.num_points(
+            input(proton_charges, num_centers)?,
+            center_index,
+            &center_coordinates(num_centers, center_x_bohr, center_y_bohr, center_z_bohr)?,
+        )?;
+        set_num_points(num_points, n)?;
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_atom_grid(
+    alpha_min: *const f64,
+    num_alpha_min: usize,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    num_centers: usize,
+    proton_charges: *const c_int,
+    center_x_bohr: *const f64,
+    center_y_bohr: *const f64,
+    center_z_bohr: *const f64,
+    center_index: usize,
+    hardness: usize,
+    partitioning: c_int,
+    capacity: usize,
+    grid_x_bohr: *mut f64,
+    grid_y_bohr: *mut f64,
+    grid_z_bohr: *mut f64,
+    grid_w: *mut f64,
+    num_points: *mut usize,
+) -> c_int {
+    guard(|| {
+        let builder = lmg_builder(
+            alpha_min_map(input(alpha_min, num_alpha_min)?),
+            alpha_max,
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+        )
+        .hardness(hardness)
+        .partitioning(self::partitioning(partitioning)?);
+        let proton_charges = input(proton_charges, num_centers)?;
+        let center_coordinates_bohr =
+            center_coordinates(num_centers, center_x_bohr, center_y_bohr, center_z_bohr)?;
+
+        // the count is cheap, this way nothing is computed if the buffers
+        // are too small
+        let n = builder.num_points(proton_charges, center_index, &center_coordinates_bohr)?;
+        set_num_points(num_points, n)?;
+        check_capacity(capacity, n)?;
+
+        let grid = builder.build_soa(proton_charges, center_index, &center_coordinates_bohr)?;
+        output(grid_x_bohr, n)?.copy_from_slice(&grid.x);
+        output(grid_y_bohr, n)?.copy_from_slice(&grid.y);
+        output(grid_z_bohr, n)?.copy_from_slice(&grid.z);
+        output(grid_w, n)?.copy_from_slice(&grid.w);
+        Ok(())
+    })
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_molecular_grid_num_points(
+    alpha_min: *const f64,
+    num_alpha_min: usize,
+    alpha_max: *const f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    num_centers: usize,
+    proton_charges: *const c_int,
+    center_x_bohr: *const f64,
+    center_y_bohr: *const f64,
+    center_z_bohr: *const f64,
+    num_points: *mut usize,
+) -> c_int {
+    guard(|| {
+        let n = molecular_grid_num_points(
+            input(alpha_min, num_centers * num_alpha_min)?,
+            num_alpha_min,
+            input(alpha_max, num_centers)?,
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            input(proton_charges, num_centers)?,
+            &center_coordinates(num_centers, center_x_bohr, center_y_bohr, center_z_bohr)?,
+        )?;
+        set_num_points(num_points, n)?;
+        Ok(())
+    })
+}
+
+fn molecular_grid_num_points(
+    alpha_min: &[f64],
+    num_alpha_min: usize,
+    alpha_max: &[f64],
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: &[i32],
+    center_coordinates_bohr: &[(f64, f64, f64)],
+) -> Result<usize, NumgridError> {
+    (0..center_coordinates_bohr.len())
+        .map(|center_index| {
+            let offset = center_index * num_alpha_min;
+            lmg_builder(
+                alpha_min_map(&alpha_min[offset..offset + num_alpha_min]),
+                alpha_max[center_index],
+                radial_precision,
+                min_num_angular_points,
+                max_num_angular_points,
+            )
+            .num_points(proton_charges, center_index, center_coordinates_bohr)
+        })
+        .sum()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn numgrid_molecular_grid(
+    alpha_min: *const f64,
+    num_alpha_min: usize,
+    alpha_max: *const f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    num_centers: usize,
+    proton_charges: *const c_int,
+    center_x_bohr: *const f64,
+    center_y_bohr: *const f64,
+    center_z_bohr: *const f64,
+    hardness: usize,
+    partitioning: c_int,
+    capacity: usize,
+    grid_x_bohr: *mut f64,
+    grid_y_bohr: *mut f64,
+    grid_z_bohr: *mut f64,
+    grid_w: *mut f64,
+    center_indices: *mut usize,
+    num_points: *mut usize,
+) -> c_int {
+    guard(|| {
+        let alpha_min = input(alpha_min, num_centers * num_alpha_min)?;
+        let alpha_max = input(alpha_max, num_centers)?;
+        let proton_charges = input(proton_charges, num_centers)?;
+        let center_coordinates_bohr =
+            center_coordinates(num_centers, center_x_bohr, center_y_bohr, center_z_bohr)?;
+
+        let n = molecular_grid_num_points(
+            alpha_min,
+            num_alpha_min,
+            alpha_max,
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            proton_charges,
+            &center_coordinates_bohr,
+        )?;
+        set_num_points(num_points, n)?;
+        check_capacity(capacity, n)?;
+
+        let radial_schemes = molecule::lmg_schemes(
+            (0..num_centers)
+                .map(|i| alpha_min_map(&alpha_min[i * num_alpha_min..(i + 1) * num_alpha_min]))
+                .collect(),
+            alpha_max.to_vec(),
+            radial_precision,
+            num_centers,
+        )?;
+        let (grid, indices) = MolecularGridBuilder::new(radial_schemes)
+            .angular_points(min_num_angular_points, max_num_angular_points)
+            .hardness(hardness)
+            .partitioning(self::partitioning(partitioning)?)
+            .build_soa(proton_charges, &center_coordinates_bohr)?;
+        output(grid_x_bohr, n)?.copy_from_slice(&grid.x);
+        output(grid_y_bohr, n)?.copy_from_slice(&grid.y);
+        output(grid_z_bohr, n)?.copy_from_slice(&grid.z);
+        output(grid_w, n)?.copy_from_slice(&grid.w);
+        output(center_indices, indices.len())?.copy_from_slice(&indices);
+        Ok(())
+    })
+}
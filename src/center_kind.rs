@@ -0,0 +1,76 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::error::NumgridError;
+
+/// Role of a center in the molecule.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CenterKind {
+    /// Owns grid points and takes part in the partitioning.
+    Atom,
+    /// Like an atom but without nuclear charge, e.g. for counterpoise
+    /// corrections. The proton charge selects the radii.
+    Ghost,
+    /// External charge which neither owns grid points nor takes part in
+    /// the partitioning. Its proton charge is not used.
+    PointCharge,
+}
+
+impl CenterKind {
+    pub fn has_grid(self) -> bool {
+        self != CenterKind::PointCharge
+    }
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for CenterKind {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        match name.to_lowercase().replace(' ', "_").as_str() {
+            "atom" => Ok(CenterKind::Atom),
+            "ghost" => Ok(CenterKind::Ghost),
+            "point_charge" => Ok(CenterKind::PointCharge),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown center kind '{}', allowed are: 'atom', 'ghost', 'point_charge'",
+                name
+            ))),
+        }
+    }
+}
+
+// indices of the centers which own grid points, all centers if no kinds
+// are given
+pub fn grid_centers(
+    center_kinds: Option<&[CenterKind]>,
+    num_centers: usize,
+) -> Result<Vec<usize>, NumgridError> {
+    match center_kinds {
+        None => Ok((0..num_centers).collect()),
+        Some(kinds) => {
+            if kinds.len() != num_centers {
+                return Err(NumgridError::InvalidInput(format!(
+                    "{} center kinds but {} centers",
+                    kinds.len(),
+                    num_centers
+                )));
+            }
+            Ok((0..num_centers).filter(|&i| kinds[i].has_grid()).collect())
+        }
+    }
+}
+
+#[test]
+fn test_grid_centers() {
+    assert_eq!(grid_centers(None, 3).unwrap(), vec![0, 1, 2]);
+
+    let kinds = [
+        CenterKind::Atom,
+        CenterKind::PointCharge,
+        CenterKind::Ghost,
+        CenterKind::PointCharge,
+    ];
+    assert_eq!(grid_centers(Some(&kinds), 4).unwrap(), vec![0, 2]);
+    assert!(grid_centers(Some(&kinds), 3).is_err());
+}
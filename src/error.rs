@@ -0,0 +1,54 @@
+use std::fmt;
+
+#[derive(Debug)]
+pub enum NumgridError {
+    /// Requested number of angular points is not one of the Lebedev grids.
+    UnsupportedAngularGrid {
+        num_points: usize,
+        allowed: Vec<usize>,
+    },
+    /// No atomic data (radius, element symbol, ...) for this proton charge.
+    UnsupportedElement(i32),
+    /// Arguments which are out of range or inconsistent with each other.
+    InvalidInput(String),
+    /// Basis set or element could not be found.
+    NotFound(String),
+    /// Basis set file which could not be read.
+    Io(String),
+    /// Basis set data which could not be parsed.
+    Parse(String),
+    /// Basis Set Exchange request which failed.
+    Network(String),
+}
+
+impl fmt::Display for NumgridError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            NumgridError::UnsupportedAngularGrid {
+                num_points,
+                allowed,
+            } => write!(
+                f,
+                "unsupported number of angular points {}, allowed are: {:?}",
+                num_points, allowed
+            ),
+            NumgridError::UnsupportedElement(charge) => {
+                write!(f, "element with proton charge {} is not supported", charge)
+            }
+            NumgridError::InvalidInput(message) => write!(f, "invalid input: {}", message),
+            NumgridError::NotFound(message) => write!(f, "not found: {}", message),
+            NumgridError::Io(message) => write!(f, "I/O error: {}", message),
+            NumgridError::Parse(message) => write!(f, "parse error: {}", message),
+            NumgridError::Network(message) => write!(f, "network error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for NumgridError {}
+
+#[cfg(feature = "bse-online")]
+impl From<reqwest::Error> for NumgridError {
+    fn from(e: reqwest::Error) -> Self {
+        NumgridError::Network(e.to_string())
+    }
+}
@@ -0,0 +1,77 @@
+/// Grid points and weights in structure-of-arrays layout: one contiguous
+/// vector per coordinate, so they can be passed to vectorized kernels or
+/// foreign code without transposing.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GridBatch {
+    pub x: Vec<f64>,
+    pub y: Vec<f64>,
+    pub z: Vec<f64>,
+    pub w: Vec<f64>,
+}
+
+impl GridBatch {
+    pub fn with_capacity(capacity: usize) -> Self {
+        GridBatch {
+            x: Vec::with_capacity(capacity),
+            y: Vec::with_capacity(capacity),
+            z: Vec::with_capacity(capacity),
+            w: Vec::with_capacity(capacity),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.w.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.w.is_empty()
+    }
+
+    pub fn push(&mut self, (x, y, z): (f64, f64, f64), w: f64) {
+        self.x.push(x);
+        self.y.push(y);
+        self.z.push(z);
+        self.w.push(w);
+    }
+
+    pub fn point(&self, i: usize) -> (f64, f64, f64) {
+        (self.x[i], self.y[i], self.z[i])
+    }
+}
+
+/// Converts the (coordinates, weights) returned by the grid functions.
+impl From<(Vec<(f64, f64, f64)>, Vec<f64>)> for GridBatch {
+    fn from((coordinates, weights): (Vec<(f64, f64, f64)>, Vec<f64>)) -> Self {
+        let mut batch = GridBatch::with_capacity(weights.len());
+        for (point, w) in coordinates.into_iter().zip(weights) {
+            batch.push(point, w);
+        }
+        batch
+    }
+}
+
+impl From<GridBatch> for (Vec<(f64, f64, f64)>, Vec<f64>) {
+    fn from(batch: GridBatch) -> Self {
+        let coordinates = (0..batch.len()).map(|i| batch.point(i)).collect();
+        (coordinates, batch.w)
+    }
+}
+
+#[test]
+fn test_conversion() {
+    let coordinates = vec![(1.0, 2.0, 3.0), (4.0, 5.0, 6.0)];
+    let weights = vec![0.5, 0.25];
+
+    let batch = GridBatch::from((coordinates.clone(), weights.clone()));
+    assert_eq!(batch.x, [1.0, 4.0]);
+    assert_eq!(batch.y, [2.0, 5.0]);
+    assert_eq!(batch.z, [3.0, 6.0]);
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch.point(1), (4.0, 5.0, 6.0));
+
+    let (c, w): (Vec<(f64, f64, f64)>, Vec<f64>) = batch.into();
+    assert_eq!(c, coordinates);
+    assert_eq!(w, weights);
+
+    assert!(GridBatch::default().is_empty());
+}
@@ -1,32 +1,37 @@
-use pyo3::prelude::*;
+#![allow(clippy::type_complexity)]
 
+use crate::error::NumgridError;
 use crate::tables;
 
-#[pyfunction]
-pub fn angular_grid(num_points: usize) -> (Vec<(f64, f64, f64)>, Vec<f64>) {
+pub fn angular_grid(num_points: usize) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>), NumgridError> {
     let offsets = tables::offsets::offsets();
 
     let offset: usize = match offsets.get(&num_points) {
         Some(v) => *v,
-        None => panic!(
-            "angular_grid called with unsupported num_points, allowed are: {:?}",
-            valid_input_values()
-        ),
+        None => {
+            return Err(NumgridError::UnsupportedAngularGrid {
+                num_points,
+                allowed: valid_input_values(),
+            })
+        }
     };
 
-    (
+    Ok((
         tables::coordinates::COORDINATES[offset..(offset + num_points)].to_vec(),
         tables::weights::WEIGHTS[offset..(offset + num_points)].to_vec(),
-    )
+    ))
 }
 
-pub fn get_closest_num_angular(n: usize) -> usize {
+pub fn get_closest_num_angular(n: usize) -> Result<usize, NumgridError> {
     for number in valid_input_values() {
         if number >= n {
-            return number;
+            return Ok(number);
         }
     }
-    panic!("input n too high in get_closest_num_angular");
+    Err(NumgridError::UnsupportedAngularGrid {
+        num_points: n,
+        allowed: valid_input_values(),
+    })
 }
 
 fn valid_input_values() -> Vec<usize> {
@@ -40,3 +45,10 @@ fn valid_input_values() -> Vec<usize> {
 
     valid_numbers
 }
+
+#[test]
+fn test_unsupported_num_points() {
+    assert!(angular_grid(15).is_err());
+    assert_eq!(get_closest_num_angular(15).unwrap(), 26);
+    assert!(get_closest_num_angular(6000).is_err());
+}
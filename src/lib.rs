@@ -1,19 +1,63 @@
 //! Describe me ...
 
 mod atom;
+mod basis_file;
+mod batch;
 mod becke_partitioning;
 mod bragg;
 mod bse;
+mod c_api;
+mod center_kind;
 mod comparison;
+mod error;
+mod grid_batch;
 mod lebedev;
+mod molecule;
+mod neighbors;
 mod parameters;
+mod pruning;
+#[cfg(feature = "python")]
 mod python;
+mod quality;
 mod radial;
+mod radii;
+mod standard_grids;
 mod tables;
+mod weight_screening;
 
 pub use crate::atom::atom_grid;
 pub use crate::atom::atom_grid_bse;
+pub use crate::atom::atom_grid_from_radial;
+pub use crate::atom::atom_grid_gradient;
+pub use crate::atom::atom_grid_quality;
+pub use crate::atom::standard_atom_grid;
+pub use crate::atom::AtomGridBatches;
+pub use crate::atom::AtomGridBuilder;
+pub use crate::basis_file::alpha_min_and_max;
+pub use crate::batch::batch_grid;
+pub use crate::batch::Batch;
+pub use crate::becke_partitioning::Partitioning;
+pub use crate::center_kind::CenterKind;
+pub use crate::error::NumgridError;
+pub use crate::grid_batch::GridBatch;
 pub use crate::lebedev::angular_grid;
+pub use crate::molecule::molecular_grid;
+pub use crate::molecule::molecular_grid_quality;
+pub use crate::molecule::standard_molecular_grid;
+pub use crate::molecule::MolecularGridBuilder;
+pub use crate::pruning::Pruning;
+pub use crate::quality::grid_parameters;
+pub use crate::quality::GridParameters;
+pub use crate::quality::Quality;
+pub use crate::radial::radial_grid_becke;
+pub use crate::radial::radial_grid_em;
 pub use crate::radial::radial_grid_kk;
 pub use crate::radial::radial_grid_lmg;
 pub use crate::radial::radial_grid_lmg_bse;
+pub use crate::radial::radial_grid_mk;
+pub use crate::radial::radial_grid_ta;
+pub use crate::radial::RadialScheme;
+pub use crate::radii::AtomicRadii;
+pub use crate::standard_grids::StandardGrid;
+pub use crate::weight_screening::screen_weights;
+pub use crate::weight_screening::ScreenedGrid;
@@ -0,0 +1,341 @@
+#![allow(clippy::too_many_arguments, clippy::type_complexity)]
+
+use rayon::prelude::*;
+
+use std::collections::HashMap;
+
+use crate::atom;
+use crate::atom::AtomGridBuilder;
+use crate::becke_partitioning::Partitioning;
+use crate::center_kind::CenterKind;
+use crate::error::NumgridError;
+use crate::grid_batch::GridBatch;
+use crate::pruning::Pruning;
+use crate::quality::Quality;
+use crate::radial::RadialScheme;
+use crate::radii::AtomicRadii;
+use crate::standard_grids::StandardGrid;
+
+/// Grid for the whole molecule: coordinates, weights, and for each point the
+/// index of the center which owns it. Points are ordered by center.
+pub fn molecular_grid(
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    hardness: usize,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>), NumgridError> {
+    let radial_schemes = lmg_schemes(
+        alpha_min,
+        alpha_max,
+        radial_precision,
+        center_coordinates_bohr.len(),
+    )?;
+
+    MolecularGridBuilder::new(radial_schemes)
+        .angular_points(min_num_angular_points, max_num_angular_points)
+        .hardness(hardness)
+        .partitioning(partitioning)
+        .build(&proton_charges, &center_coordinates_bohr)
+}
+
+// one LMG radial scheme per center
+pub(crate) fn lmg_schemes(
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    radial_precision: f64,
+    num_centers: usize,
+) -> Result<Vec<RadialScheme>, NumgridError> {
+    if alpha_min.len() != num_centers || alpha_max.len() != num_centers {
+        return Err(NumgridError::InvalidInput(format!(
+            "alpha_min and alpha_max need one entry for each of the {} centers",
+            num_centers
+        )));
+    }
+
+    let radial_schemes = alpha_min
+        .into_iter()
+        .zip(alpha_max)
+        .map(|(alpha_min, alpha_max)| RadialScheme::Lmg {
+            alpha_min,
+            alpha_max,
+            radial_precision,
+        })
+        .collect();
+
+    Ok(radial_schemes)
+}
+
+/// Molecular grid with one radial scheme per center and the options of
+/// `AtomGridBuilder` shared by all centers.
+///
+/// ```
+/// use numgrid::{MolecularGridBuilder, Pruning, RadialScheme};
+///
+/// let (coordinates, weights, center_indices) =
+///     MolecularGridBuilder::new(vec![RadialScheme::Kk { num_points: 50 }; 3])
+///         .angular_points(50, 110)
+///         .pruning(Pruning::Intervals {
+///             boundaries: vec![0.5],
+///             num_points: vec![50, 110],
+///         })
+///         .build(&[8, 1, 1], &[(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)])
+///         .unwrap();
+/// assert_eq!(coordinates.len(), weights.len());
+/// assert_eq!(center_indices.len(), weights.len());
+/// ```
+#[derive(Clone, Debug)]
+pub struct MolecularGridBuilder {
+    radial_schemes: Vec<RadialScheme>,
+    // options shared by all centers; the radial scheme is set per center
+    options: AtomGridBuilder,
+}
+
+impl MolecularGridBuilder {
+    pub fn new(radial_schemes: Vec<RadialScheme>) -> Self {
+        MolecularGridBuilder {
+            radial_schemes,
+            options: AtomGridBuilder::new(RadialScheme::Explicit {
+                radii: Vec::new(),
+                weights: Vec::new(),
+            }),
+        }
+    }
+
+    /// The radial grid and fixed pruning of a standard grid for all centers.
+    pub fn standard(grid: StandardGrid, num_centers: usize) -> Self {
+        MolecularGridBuilder::new(vec![RadialScheme::Standard(grid); num_centers])
+            .pruning(Pruning::Standard(grid))
+    }
+
+    pub fn angular_points(mut self, min_num_points: usize, max_num_points: usize) -> Self {
+        self.options = self.options.angular_points(min_num_points, max_num_points);
+        self
+    }
+
+    pub fn hardness(mut self, hardness: usize) -> Self {
+        self.options = self.options.hardness(hardness);
+        self
+    }
+
+    pub fn partitioning(mut self, partitioning: Partitioning) -> Self {
+        self.options = self.options.partitioning(partitioning);
+        self
+    }
+
+    pub fn pruning(mut self, pruning: Pruning) -> Self {
+        self.options = self.options.pruning(pruning);
+        self
+    }
+
+    pub fn atomic_radii(mut self, atomic_radii: AtomicRadii) -> Self {
+        self.options = self.options.atomic_radii(atomic_radii);
+        self
+    }
+
+    pub fn center_kinds(mut self, center_kinds: Vec<CenterKind>) -> Self {
+        self.options = self.options.center_kinds(center_kinds);
+        self
+    }
+
+    pub fn screening(mut self, screening: bool) -> Self {
+        self.options = self.options.screening(screening);
+        self
+    }
+
+    pub fn build(
+        &self,
+        proton_charges: &[i32],
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>), NumgridError> {
+        let atom_grids = self.atom_grids(center_coordinates_bohr, |builder, center_index| {
+            builder.build(proton_charges, center_index, center_coordinates_bohr)
+        })?;
+
+        Ok(concatenate(atom_grids))
+    }
+
+    /// Like `build` but returns the grid in structure-of-arrays layout,
+    /// together with the index of the center which owns each point.
+    pub fn build_soa(
+        &self,
+        proton_charges: &[i32],
+        center_coordinates_bohr: &[(f64, f64, f64)],
+    ) -> Result<(GridBatch, Vec<usize>), NumgridError> {
+        let atom_grids = self.atom_grids(center_coordinates_bohr, |builder, center_index| {
+            builder.build_soa(proton_charges, center_index, center_coordinates_bohr)
+        })?;
+
+        Ok(concatenate_soa(atom_grids))
+    }
+
+    // one grid for each center, computed with the builder of that center
+    fn atom_grids<T: Send>(
+        &self,
+        center_coordinates_bohr: &[(f64, f64, f64)],
+        build: impl Fn(&AtomGridBuilder, usize) -> Result<T, NumgridError> + Sync,
+    ) -> Result<Vec<T>, NumgridError> {
+        let num_centers = center_coordinates_bohr.len();
+        if self.radial_schemes.len() != num_centers {
+            return Err(NumgridError::InvalidInput(format!(
+                "{} radial schemes for {} centers",
+                self.radial_schemes.len(),
+                num_centers
+            )));
+        }
+
+        // atoms are processed in parallel and within each atom
+        // the partitioning is parallelized over points
+        self.radial_schemes
+            .par_iter()
+            .enumerate()
+            .map(|(center_index, radial_scheme)| {
+                let builder = self.options.clone().radial_scheme(radial_scheme.clone());
+                build(&builder, center_index)
+            })
+            .collect()
+    }
+}
+
+/// Like `molecular_grid` but with radial precision, angular points, and
+/// hardness taken from a named quality level for the element of each center.
+pub fn molecular_grid_quality(
+    quality: Quality,
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>), NumgridError> {
+    let atom_grids = quality_atom_grids(
+        quality,
+        alpha_min,
+        alpha_max,
+        &proton_charges,
+        &center_coordinates_bohr,
+        partitioning,
+        |builder, center_index| {
+            builder.build(&proton_charges, center_index, &center_coordinates_bohr)
+        },
+    )?;
+
+    Ok(concatenate(atom_grids))
+}
+
+// like molecular_grid_quality but in structure-of-arrays layout, for soa=True
+// in Python
+#[cfg(feature = "python")]
+pub(crate) fn molecular_grid_quality_soa(
+    quality: Quality,
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    proton_charges: &[i32],
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    partitioning: Partitioning,
+) -> Result<(GridBatch, Vec<usize>), NumgridError> {
+    let atom_grids = quality_atom_grids(
+        quality,
+        alpha_min,
+        alpha_max,
+        proton_charges,
+        center_coordinates_bohr,
+        partitioning,
+        |builder, center_index| {
+            builder.build_soa(proton_charges, center_index, center_coordinates_bohr)
+        },
+    )?;
+
+    Ok(concatenate_soa(atom_grids))
+}
+
+// one grid for each center, with the quality parameters of its element
+fn quality_atom_grids<T: Send>(
+    quality: Quality,
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    proton_charges: &[i32],
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    partitioning: Partitioning,
+    build: impl Fn(&AtomGridBuilder, usize) -> Result<T, NumgridError> + Sync,
+) -> Result<Vec<T>, NumgridError> {
+    let num_centers = center_coordinates_bohr.len();
+    if alpha_min.len() != num_centers || alpha_max.len() != num_centers {
+        return Err(NumgridError::InvalidInput(format!(
+            "alpha_min and alpha_max need one entry for each of the {} centers",
+            num_centers
+        )));
+    }
+
+    alpha_min
+        .into_par_iter()
+        .zip(alpha_max.into_par_iter())
+        .enumerate()
+        .map(|(center_index, (alpha_min_atom, alpha_max_atom))| {
+            let builder = atom::quality_builder(
+                quality,
+                alpha_min_atom,
+                alpha_max_atom,
+                proton_charges,
+                center_index,
+                center_coordinates_bohr,
+                partitioning,
+            )?;
+            build(&builder, center_index)
+        })
+        .collect()
+}
+
+/// Like `molecular_grid` but with the radial grid and fixed pruning of a
+/// standard grid.
+pub fn standard_molecular_grid(
+    grid: StandardGrid,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+    hardness: usize,
+    partitioning: Partitioning,
+) -> Result<(Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>), NumgridError> {
+    MolecularGridBuilder::standard(grid, center_coordinates_bohr.len())
+        .hardness(hardness)
+        .partitioning(partitioning)
+        .build(&proton_charges, &center_coordinates_bohr)
+}
+
+// concatenates atom grids which are ordered by center
+fn concatenate(
+    atom_grids: Vec<(Vec<(f64, f64, f64)>, Vec<f64>)>,
+) -> (Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>) {
+    let num_points = atom_grids.iter().map(|(_, ws)| ws.len()).sum();
+
+    let mut coordinates = Vec::with_capacity(num_points);
+    let mut weights = Vec::with_capacity(num_points);
+    let mut center_indices = Vec::with_capacity(num_points);
+
+    for (center_index, (coordinates_atom, weights_atom)) in atom_grids.into_iter().enumerate() {
+        center_indices.extend(std::iter::repeat_n(center_index, weights_atom.len()));
+        coordinates.extend(coordinates_atom);
+        weights.extend(weights_atom);
+    }
+
+    (coordinates, weights, center_indices)
+}
+
+// like concatenate but in structure-of-arrays layout
+fn concatenate_soa(atom_grids: Vec<GridBatch>) -> (GridBatch, Vec<usize>) {
+    let num_points = atom_grids.iter().map(|grid| grid.len()).sum();
+    let mut grid = GridBatch::with_capacity(num_points);
+    let mut center_indices = Vec::with_capacity(num_points);
+
+    for (center_index, atom_grid) in atom_grids.into_iter().enumerate() {
+        center_indices.extend(std::iter::repeat_n(center_index, atom_grid.len()));
+        grid.x.extend(atom_grid.x);
+        grid.y.extend(atom_grid.y);
+        grid.z.extend(atom_grid.z);
+        grid.w.extend(atom_grid.w);
+    }
+
+    (grid, center_indices)
+}
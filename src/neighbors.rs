@@ -0,0 +1,135 @@
+// uniform grid of cells over the centers to find all centers within a given
+// distance of a point without looping over all of them
+pub struct CellList {
+    origin: (f64, f64, f64),
+    cell_size: f64,
+    dims: [usize; 3],
+    // centers of cell i are center_indices[offsets[i]..offsets[i + 1]]
+    offsets: Vec<usize>,
+    center_indices: Vec<usize>,
+    center_coordinates_bohr: Vec<(f64, f64, f64)>,
+}
+
+impl CellList {
+    pub fn new(center_coordinates_bohr: &[(f64, f64, f64)], cell_size: f64) -> CellList {
+        let mut min = (f64::MAX, f64::MAX, f64::MAX);
+        let mut max = (f64::MIN, f64::MIN, f64::MIN);
+        for c in center_coordinates_bohr {
+            min = (min.0.min(c.0), min.1.min(c.1), min.2.min(c.2));
+            max = (max.0.max(c.0), max.1.max(c.1), max.2.max(c.2));
+        }
+        if center_coordinates_bohr.is_empty() {
+            min = (0.0, 0.0, 0.0);
+            max = (0.0, 0.0, 0.0);
+        }
+
+        // sparse systems (far away centers) would give too many empty cells
+        let num_centers = center_coordinates_bohr.len().max(1) as f64;
+        let volume =
+            (max.0 - min.0 + cell_size) * (max.1 - min.1 + cell_size) * (max.2 - min.2 + cell_size);
+        let cell_size = cell_size.max((volume / (8.0 * num_centers)).cbrt());
+
+        let dim = |a: f64, b: f64| ((b - a) / cell_size) as usize + 1;
+        let dims = [dim(min.0, max.0), dim(min.1, max.1), dim(min.2, max.2)];
+
+        let mut cells = vec![Vec::new(); dims[0] * dims[1] * dims[2]];
+        for (i, c) in center_coordinates_bohr.iter().enumerate() {
+            let ix = ((c.0 - min.0) / cell_size) as usize;
+            let iy = ((c.1 - min.1) / cell_size) as usize;
+            let iz = ((c.2 - min.2) / cell_size) as usize;
+            cells[(ix * dims[1] + iy) * dims[2] + iz].push(i);
+        }
+
+        let mut offsets = Vec::with_capacity(cells.len() + 1);
+        let mut center_indices = Vec::with_capacity(center_coordinates_bohr.len());
+        offsets.push(0);
+        for cell in cells {
+            center_indices.extend(cell);
+            offsets.push(center_indices.len());
+        }
+
+        CellList {
+            origin: min,
+            cell_size,
+            dims,
+            offsets,
+            center_indices,
+            center_coordinates_bohr: center_coordinates_bohr.to_vec(),
+        }
+    }
+
+    // indices (in ascending order) of all centers within radius of the point
+    pub fn within(&self, point: (f64, f64, f64), radius: f64, indices: &mut Vec<usize>) {
+        indices.clear();
+
+        let range = |p: f64, o: f64, n: usize| -> Option<(usize, usize)> {
+            let lo = ((p - radius - o) / self.cell_size).floor();
+            let hi = ((p + radius - o) / self.cell_size).floor();
+            if hi < 0.0 || lo >= n as f64 {
+                return None;
+            }
+            Some((lo.max(0.0) as usize, (hi as usize).min(n - 1)))
+        };
+
+        let (Some(rx), Some(ry), Some(rz)) = (
+            range(point.0, self.origin.0, self.dims[0]),
+            range(point.1, self.origin.1, self.dims[1]),
+            range(point.2, self.origin.2, self.dims[2]),
+        ) else {
+            return;
+        };
+
+        let radius2 = radius * radius;
+        for ix in rx.0..=rx.1 {
+            for iy in ry.0..=ry.1 {
+                for iz in rz.0..=rz.1 {
+                    let cell = (ix * self.dims[1] + iy) * self.dims[2] + iz;
+                    for &i in &self.center_indices[self.offsets[cell]..self.offsets[cell + 1]] {
+                        let c = self.center_coordinates_bohr[i];
+                        let d2 = (c.0 - point.0).powi(2)
+                            + (c.1 - point.1).powi(2)
+                            + (c.2 - point.2).powi(2);
+                        if d2 <= radius2 {
+                            indices.push(i);
+                        }
+                    }
+                }
+            }
+        }
+
+        indices.sort_unstable();
+    }
+}
+
+#[test]
+fn test_within() {
+    let centers: Vec<(f64, f64, f64)> = (0..1000)
+        .map(|i| {
+            let x = (i % 10) as f64 * 1.7;
+            let y = ((i / 10) % 10) as f64 * 1.9;
+            let z = (i / 100) as f64 * 2.3;
+            (x, y, z)
+        })
+        .collect();
+    let cell_list = CellList::new(&centers, 3.0);
+
+    let mut indices = Vec::new();
+    for &point in &[
+        (0.0, 0.0, 0.0),
+        (7.3, 8.1, 10.2),
+        (-20.0, 5.0, 5.0),
+        (3.0, 3.0, 30.0),
+    ] {
+        for &radius in &[0.5, 2.0, 6.0, 100.0] {
+            cell_list.within(point, radius, &mut indices);
+            let reference: Vec<usize> = (0..centers.len())
+                .filter(|&i| {
+                    let c = centers[i];
+                    (c.0 - point.0).powi(2) + (c.1 - point.1).powi(2) + (c.2 - point.2).powi(2)
+                        <= radius * radius
+                })
+                .collect();
+            assert_eq!(indices, reference);
+        }
+    }
+}
@@ -0,0 +1,189 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use std::fmt;
+use std::sync::Arc;
+
+use crate::error::NumgridError;
+use crate::lebedev;
+use crate::standard_grids;
+use crate::standard_grids::StandardGrid;
+
+/// Number of angular points at a given radius.
+#[derive(Clone)]
+pub enum Pruning {
+    /// Linear in r below a fifth of the atomic radius (by default the Bragg
+    /// radius) between the minimum and maximum number of angular points, as in
+    /// DIRAC. This is the default.
+    Bragg,
+    /// `num_points[i]` angular points below `boundaries[i]` (and above
+    /// `boundaries[i - 1]`), the last entry of `num_points` beyond the last
+    /// boundary. Boundaries are in units of the atomic radius (by default the
+    /// Bragg radius).
+    Intervals {
+        boundaries: Vec<f64>,
+        num_points: Vec<usize>,
+    },
+    /// Function of proton charge and radius (bohr) which returns the number
+    /// of angular points.
+    Custom(Arc<dyn Fn(i32, f64) -> Result<usize, NumgridError> + Send + Sync>),
+    /// Fixed regions of a standard grid.
+    Standard(StandardGrid),
+}
+
+impl fmt::Debug for Pruning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Pruning::Bragg => write!(f, "Bragg"),
+            Pruning::Intervals {
+                boundaries,
+                num_points,
+            } => f
+                .debug_struct("Intervals")
+                .field("boundaries", boundaries)
+                .field("num_points", num_points)
+                .finish(),
+            Pruning::Custom(_) => write!(f, "Custom(..)"),
+            Pruning::Standard(grid) => f.debug_tuple("Standard").field(grid).finish(),
+        }
+    }
+}
+
+// accepts "bragg", a standard grid name, a tuple (boundaries, num_points),
+// or a callable f(proton_charge, r) -> num_points
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for Pruning {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(name) = ob.extract::<String>() {
+            if name.to_lowercase() == "bragg" {
+                return Ok(Pruning::Bragg);
+            }
+            return ob.extract().map(Pruning::Standard).map_err(|_| {
+                PyValueError::new_err(format!(
+                    "unknown pruning '{}', allowed are: 'bragg', 'sg1'",
+                    name
+                ))
+            });
+        }
+        if let Ok((boundaries, num_points)) = ob.extract::<(Vec<f64>, Vec<usize>)>() {
+            return Ok(Pruning::Intervals {
+                boundaries,
+                num_points,
+            });
+        }
+        if ob.is_callable() {
+            let function: Py<PyAny> = ob.clone().unbind();
+            return Ok(Pruning::Custom(Arc::new(move |proton_charge, r| {
+                Python::with_gil(|py| {
+                    function
+                        .call1(py, (proton_charge, r))
+                        .and_then(|n| n.extract::<usize>(py))
+                        .map_err(|e| {
+                            NumgridError::InvalidInput(format!("pruning function failed: {}", e))
+                        })
+                })
+            })));
+        }
+        Err(PyValueError::new_err(
+            "pruning has to be 'bragg', a standard grid name, a tuple (boundaries, num_points), or a callable",
+        ))
+    }
+}
+
+impl Pruning {
+    pub fn num_angular_points(
+        &self,
+        proton_charge: i32,
+        atomic_radius_angstrom: f64,
+        radii: &[f64],
+        min_num_angular_points: usize,
+        max_num_angular_points: usize,
+    ) -> Result<Vec<usize>, NumgridError> {
+        match self {
+            Pruning::Bragg => {
+                // factors match DIRAC code
+                let rb = atomic_radius_angstrom / (5.0 * 0.529177249);
+                radii
+                    .iter()
+                    .map(|&r| bragg_pruning(r, rb, min_num_angular_points, max_num_angular_points))
+                    .collect()
+            }
+            Pruning::Intervals {
+                boundaries,
+                num_points,
+            } => {
+                if num_points.len() != boundaries.len() + 1 {
+                    return Err(NumgridError::InvalidInput(format!(
+                        "{} pruning boundaries need {} numbers of angular points, got {}",
+                        boundaries.len(),
+                        boundaries.len() + 1,
+                        num_points.len()
+                    )));
+                }
+                if boundaries.windows(2).any(|w| w[0] > w[1]) {
+                    return Err(NumgridError::InvalidInput(
+                        "pruning boundaries have to be in ascending order".to_string(),
+                    ));
+                }
+                let rb = atomic_radius_angstrom / 0.529177249;
+                Ok(radii
+                    .iter()
+                    .map(|&r| num_points[boundaries.iter().filter(|&&b| r >= b * rb).count()])
+                    .collect())
+            }
+            Pruning::Custom(function) => {
+                radii.iter().map(|&r| function(proton_charge, r)).collect()
+            }
+            Pruning::Standard(grid) => radii
+                .iter()
+                .map(|&r| standard_grids::num_angular_points(*grid, proton_charge, r))
+                .collect(),
+        }
+    }
+}
+
+fn bragg_pruning(
+    r: f64,
+    rb: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+) -> Result<usize, NumgridError> {
+    let mut num_angular = max_num_angular_points;
+    if r < rb {
+        num_angular = ((max_num_angular_points as f64) * r / rb) as usize;
+        num_angular = lebedev::get_closest_num_angular(num_angular)?;
+        if num_angular < min_num_angular_points {
+            num_angular = min_num_angular_points;
+        }
+    }
+    Ok(num_angular)
+}
+
+#[test]
+fn test_intervals() {
+    // oxygen, Bragg radius 0.60 angstrom
+    let rb = 0.60 / 0.529177249;
+    let pruning = Pruning::Intervals {
+        boundaries: vec![0.25, 0.5, 1.0],
+        num_points: vec![14, 50, 110, 302],
+    };
+    let radii = [0.1 * rb, 0.3 * rb, 0.7 * rb, 1.0 * rb, 5.0 * rb];
+    assert_eq!(
+        pruning.num_angular_points(8, 0.60, &radii, 0, 0).unwrap(),
+        vec![14, 50, 110, 302, 302]
+    );
+
+    let pruning = Pruning::Intervals {
+        boundaries: vec![0.5, 0.25],
+        num_points: vec![14, 50, 110],
+    };
+    assert!(pruning.num_angular_points(8, 0.60, &radii, 0, 0).is_err());
+
+    let pruning = Pruning::Intervals {
+        boundaries: vec![0.5],
+        num_points: vec![14],
+    };
+    assert!(pruning.num_angular_points(8, 0.60, &radii, 0, 0).is_err());
+}
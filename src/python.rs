@@ -1,22 +1,933 @@
+// useless_conversion is triggered by the pyfunction macro for PyResult return types
+#![allow(
+    clippy::too_many_arguments,
+    clippy::type_complexity,
+    clippy::useless_conversion
+)]
+
+use numpy::{PyArray1, PyArray2, PyArray3, PyArrayMethods, PyReadonlyArray1, PyReadonlyArray2};
+use pyo3::exceptions::{PyConnectionError, PyLookupError, PyOSError, PyValueError};
 use pyo3::prelude::*;
 
-use crate::atom::atom_grid;
-use crate::atom::atom_grid_bse;
-use crate::lebedev::angular_grid;
-use crate::radial::radial_grid_kk;
-use crate::radial::radial_grid_lmg;
-use crate::radial::radial_grid_lmg_bse;
+use std::collections::HashMap;
+
+use crate::atom;
+use crate::atom::{AtomGridBatches, AtomGridBuilder};
+use crate::basis_file::alpha_min_and_max;
+use crate::batch;
+use crate::batch::Batch;
+use crate::becke_partitioning::Partitioning;
+use crate::center_kind::CenterKind;
+use crate::error::NumgridError;
+use crate::grid_batch::GridBatch;
+use crate::lebedev;
+use crate::molecule;
+use crate::molecule::MolecularGridBuilder;
+use crate::pruning::Pruning;
+use crate::quality::grid_parameters;
+use crate::quality::Quality;
+use crate::radial;
+use crate::radial::RadialScheme;
+use crate::radii::AtomicRadii;
+use crate::standard_grids::StandardGrid;
+use crate::weight_screening;
+use crate::weight_screening::ScreenedGrid;
+
+impl From<NumgridError> for PyErr {
+    fn from(e: NumgridError) -> PyErr {
+        let message = e.to_string();
+        match e {
+            NumgridError::NotFound(_) => PyLookupError::new_err(message),
+            NumgridError::Io(_) => PyOSError::new_err(message),
+            NumgridError::Network(_) => PyConnectionError::new_err(message),
+            _ => PyValueError::new_err(message),
+        }
+    }
+}
+
+// the GIL is released while grids are computed so that other Python
+// threads can run; custom pruning functions take it again when called
+
+// grids are returned as contiguous NumPy arrays: coordinates with shape
+// (n, 3), weights and center indices with shape (n,)
+type Grid<'py> = (Bound<'py, PyArray2<f64>>, Bound<'py, PyArray1<f64>>);
+type MolecularGrid<'py> = (
+    Bound<'py, PyArray2<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<usize>>,
+);
+// with soa=True the coordinates are returned as separate x, y, and z arrays
+// of shape (n,), moved into NumPy without copying
+type SoaGrid<'py> = (
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+);
+type MolecularSoaGrid<'py> = (
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray1<usize>>,
+);
+type RadialGrid<'py> = (Bound<'py, PyArray1<f64>>, Bound<'py, PyArray1<f64>>);
+
+fn coordinates_array(
+    py: Python<'_>,
+    coordinates: Vec<(f64, f64, f64)>,
+) -> PyResult<Bound<'_, PyArray2<f64>>> {
+    let num_points = coordinates.len();
+    let flat: Vec<f64> = coordinates
+        .into_iter()
+        .flat_map(|(x, y, z)| [x, y, z])
+        .collect();
+    PyArray1::from_vec_bound(py, flat).reshape([num_points, 3])
+}
+
+fn grid_arrays(py: Python<'_>, grid: (Vec<(f64, f64, f64)>, Vec<f64>)) -> PyResult<Grid<'_>> {
+    let (coordinates, weights) = grid;
+    Ok((
+        coordinates_array(py, coordinates)?,
+        PyArray1::from_vec_bound(py, weights),
+    ))
+}
+
+fn molecular_grid_arrays(
+    py: Python<'_>,
+    grid: (Vec<(f64, f64, f64)>, Vec<f64>, Vec<usize>),
+) -> PyResult<MolecularGrid<'_>> {
+    let (coordinates, weights, center_indices) = grid;
+    Ok((
+        coordinates_array(py, coordinates)?,
+        PyArray1::from_vec_bound(py, weights),
+        PyArray1::from_vec_bound(py, center_indices),
+    ))
+}
+
+fn soa_arrays(py: Python<'_>, grid: GridBatch) -> SoaGrid<'_> {
+    (
+        PyArray1::from_vec_bound(py, grid.x),
+        PyArray1::from_vec_bound(py, grid.y),
+        PyArray1::from_vec_bound(py, grid.z),
+        PyArray1::from_vec_bound(py, grid.w),
+    )
+}
+
+fn molecular_soa_arrays(py: Python<'_>, grid: (GridBatch, Vec<usize>)) -> MolecularSoaGrid<'_> {
+    let (grid, center_indices) = grid;
+    let (x, y, z, w) = soa_arrays(py, grid);
+    (x, y, z, w, PyArray1::from_vec_bound(py, center_indices))
+}
+
+fn radial_grid_arrays(py: Python<'_>, grid: (Vec<f64>, Vec<f64>)) -> RadialGrid<'_> {
+    let (radii, weights) = grid;
+    (
+        PyArray1::from_vec_bound(py, radii),
+        PyArray1::from_vec_bound(py, weights),
+    )
+}
+
+/// Coordinates given either as NumPy array of shape (n, 3) or as sequence of
+/// (x, y, z).
+struct Coordinates(Vec<(f64, f64, f64)>);
+
+impl<'py> FromPyObject<'py> for Coordinates {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(array) = ob.extract::<PyReadonlyArray2<f64>>() {
+            let array = array.as_array();
+            if array.ncols() != 3 {
+                return Err(PyValueError::new_err(format!(
+                    "coordinates need to have shape (n, 3), got {:?}",
+                    array.shape()
+                )));
+            }
+            return Ok(Coordinates(
+                array
+                    .rows()
+                    .into_iter()
+                    .map(|r| (r[0], r[1], r[2]))
+                    .collect(),
+            ));
+        }
+        let coordinates: Vec<[f64; 3]> = ob.extract()?;
+        Ok(Coordinates(
+            coordinates.into_iter().map(|[x, y, z]| (x, y, z)).collect(),
+        ))
+    }
+}
+
+/// Numbers given either as one-dimensional NumPy array or as sequence.
+struct Floats(Vec<f64>);
+
+impl<'py> FromPyObject<'py> for Floats {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(array) = ob.extract::<PyReadonlyArray1<f64>>() {
+            return Ok(Floats(array.as_array().to_vec()));
+        }
+        Ok(Floats(ob.extract()?))
+    }
+}
+
+// builder with the options shared by the atom grid functions
+fn atom_grid_builder(
+    radial_scheme: RadialScheme,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    hardness: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+) -> AtomGridBuilder {
+    let builder = AtomGridBuilder::new(radial_scheme)
+        .angular_points(min_num_angular_points, max_num_angular_points)
+        .hardness(hardness)
+        .partitioning(partitioning)
+        .pruning(pruning)
+        .atomic_radii(atomic_radii)
+        .screening(screening);
+
+    match center_kinds {
+        Some(center_kinds) => builder.center_kinds(center_kinds),
+        None => builder,
+    }
+}
+
+fn atom_grid_output(
+    py: Python<'_>,
+    builder: &AtomGridBuilder,
+    proton_charges: &[i32],
+    center_index: usize,
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    soa: bool,
+) -> PyResult<PyObject> {
+    if soa {
+        let grid = py.allow_threads(|| {
+            builder.build_soa(proton_charges, center_index, center_coordinates_bohr)
+        })?;
+        Ok(soa_arrays(py, grid).into_py(py))
+    } else {
+        let grid = py.allow_threads(|| {
+            builder.build(proton_charges, center_index, center_coordinates_bohr)
+        })?;
+        Ok(grid_arrays(py, grid)?.into_py(py))
+    }
+}
+
+fn molecular_grid_output(
+    py: Python<'_>,
+    builder: &MolecularGridBuilder,
+    proton_charges: &[i32],
+    center_coordinates_bohr: &[(f64, f64, f64)],
+    soa: bool,
+) -> PyResult<PyObject> {
+    if soa {
+        let grid =
+            py.allow_threads(|| builder.build_soa(proton_charges, center_coordinates_bohr))?;
+        Ok(molecular_soa_arrays(py, grid).into_py(py))
+    } else {
+        let grid = py.allow_threads(|| builder.build(proton_charges, center_coordinates_bohr))?;
+        Ok(molecular_grid_arrays(py, grid)?.into_py(py))
+    }
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    basis_set,
+    radial_precision,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    hardness,
+    partitioning = Partitioning::Becke,
+    pruning = Pruning::Bragg,
+    atomic_radii = AtomicRadii::Bragg,
+    center_kinds = None,
+    screening = false,
+    soa = false,
+))]
+fn atom_grid_bse<'py>(
+    py: Python<'py>,
+    basis_set: &str,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = atom_grid_builder(
+        RadialScheme::LmgBse {
+            basis_set: basis_set.to_string(),
+            radial_precision,
+        },
+        min_num_angular_points,
+        max_num_angular_points,
+        hardness,
+        partitioning,
+        pruning,
+        atomic_radii,
+        center_kinds,
+        screening,
+    );
+    atom_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+#[pyfunction]
+#[pyo3(signature = (
+    alpha_min,
+    alpha_max,
+    radial_precision,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    hardness,
+    partitioning = Partitioning::Becke,
+    pruning = Pruning::Bragg,
+    atomic_radii = AtomicRadii::Bragg,
+    center_kinds = None,
+    screening = false,
+    soa = false,
+))]
+fn atom_grid<'py>(
+    py: Python<'py>,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = atom_grid_builder(
+        RadialScheme::Lmg {
+            alpha_min,
+            alpha_max,
+            radial_precision,
+        },
+        min_num_angular_points,
+        max_num_angular_points,
+        hardness,
+        partitioning,
+        pruning,
+        atomic_radii,
+        center_kinds,
+        screening,
+    );
+    atom_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+/// Like `atom_grid` but returns an iterator over batches of at most
+/// `batch_size` points, in the same order as `atom_grid`. Each batch is
+/// computed when it is requested.
+#[pyfunction]
+#[pyo3(signature = (
+    alpha_min,
+    alpha_max,
+    radial_precision,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    hardness,
+    batch_size = 1024,
+    partitioning = Partitioning::Becke,
+    pruning = Pruning::Bragg,
+    atomic_radii = AtomicRadii::Bragg,
+    center_kinds = None,
+    screening = false,
+    soa = false,
+))]
+fn atom_grid_batches(
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    batch_size: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+    soa: bool,
+) -> PyResult<GridBatches> {
+    let builder = atom_grid_builder(
+        RadialScheme::Lmg {
+            alpha_min,
+            alpha_max,
+            radial_precision,
+        },
+        min_num_angular_points,
+        max_num_angular_points,
+        hardness,
+        partitioning,
+        pruning,
+        atomic_radii,
+        center_kinds,
+        screening,
+    );
+
+    let batches = builder.build_batches(
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        batch_size,
+    )?;
+    Ok(GridBatches { batches, soa })
+}
+
+/// Iterator over (coordinates, weights) batches of an atom grid, or
+/// (x, y, z, weights) batches with `soa=True`.
+#[pyclass(name = "AtomGridBatches")]
+struct GridBatches {
+    batches: AtomGridBatches,
+    soa: bool,
+}
+
+#[pymethods]
+impl GridBatches {
+    fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+        slf
+    }
+
+    fn __next__(mut slf: PyRefMut<'_, Self>, py: Python<'_>) -> PyResult<Option<PyObject>> {
+        let soa = slf.soa;
+        let batches = &mut slf.batches;
+        if soa {
+            Ok(py
+                .allow_threads(|| batches.next_soa())
+                .map(|grid| soa_arrays(py, grid).into_py(py)))
+        } else {
+            match py.allow_threads(|| batches.next()) {
+                Some(grid) => Ok(Some(grid_arrays(py, grid)?.into_py(py))),
+                None => Ok(None),
+            }
+        }
+    }
+}
+
+/// Like `atom_grid` but built on top of a radial grid computed by any of the
+/// `radial_grid_*` functions instead of the LMG grid.
+#[pyfunction]
+#[pyo3(signature = (
+    radii,
+    radial_weights,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    hardness,
+    partitioning = Partitioning::Becke,
+    pruning = Pruning::Bragg,
+    atomic_radii = AtomicRadii::Bragg,
+    center_kinds = None,
+    screening = false,
+    soa = false,
+))]
+fn atom_grid_from_radial<'py>(
+    py: Python<'py>,
+    radii: Floats,
+    radial_weights: Floats,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = atom_grid_builder(
+        RadialScheme::Explicit {
+            radii: radii.0,
+            weights: radial_weights.0,
+        },
+        min_num_angular_points,
+        max_num_angular_points,
+        hardness,
+        partitioning,
+        pruning,
+        atomic_radii,
+        center_kinds,
+        screening,
+    );
+    atom_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+/// Like `atom_grid` with Becke partitioning but also returns the derivatives
+/// of each weight with respect to the coordinates of every center.
+/// Grid points move rigidly with the center they belong to.
+/// Weight gradients are returned with shape (n, number of centers, 3).
+#[pyfunction]
+fn atom_grid_gradient<'py>(
+    py: Python<'py>,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+) -> PyResult<(
+    Bound<'py, PyArray2<f64>>,
+    Bound<'py, PyArray1<f64>>,
+    Bound<'py, PyArray3<f64>>,
+)> {
+    let num_centers = center_coordinates_bohr.0.len();
+    let (coordinates, weights, gradients) = py.allow_threads(|| {
+        atom::atom_grid_gradient(
+            alpha_min,
+            alpha_max,
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            proton_charges,
+            center_index,
+            center_coordinates_bohr.0,
+            hardness,
+        )
+    })?;
+    let num_points = gradients.len();
+    let flat: Vec<f64> = gradients
+        .into_iter()
+        .flatten()
+        .flat_map(|(x, y, z)| [x, y, z])
+        .collect();
+    let (coordinates, weights) = grid_arrays(py, (coordinates, weights))?;
+    Ok((
+        coordinates,
+        weights,
+        PyArray1::from_vec_bound(py, flat).reshape([num_points, num_centers, 3])?,
+    ))
+}
+
+/// Like `atom_grid` but with radial precision, angular points, and hardness
+/// taken from a named quality level for the element of the center.
+#[pyfunction]
+#[pyo3(signature = (
+    quality,
+    alpha_min,
+    alpha_max,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    partitioning = Partitioning::Becke,
+    soa = false,
+))]
+fn atom_grid_quality<'py>(
+    py: Python<'py>,
+    quality: Quality,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    partitioning: Partitioning,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = atom::quality_builder(
+        quality,
+        alpha_min,
+        alpha_max,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        partitioning,
+    )?;
+    atom_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+/// Atom grid with the radial grid and fixed pruning of a standard grid.
+#[pyfunction]
+#[pyo3(signature = (
+    grid,
+    proton_charges,
+    center_index,
+    center_coordinates_bohr,
+    hardness = 3,
+    partitioning = Partitioning::Becke,
+    soa = false,
+))]
+fn standard_atom_grid<'py>(
+    py: Python<'py>,
+    grid: StandardGrid,
+    proton_charges: Vec<i32>,
+    center_index: usize,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = AtomGridBuilder::standard(grid)
+        .hardness(hardness)
+        .partitioning(partitioning);
+    atom_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        center_index,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+/// Grid for the whole molecule: coordinates, weights, and for each point the
+/// index of the center which owns it. Points are ordered by center.
+#[pyfunction]
+#[pyo3(signature = (
+    alpha_min,
+    alpha_max,
+    radial_precision,
+    min_num_angular_points,
+    max_num_angular_points,
+    proton_charges,
+    center_coordinates_bohr,
+    hardness,
+    partitioning = Partitioning::Becke,
+    pruning = Pruning::Bragg,
+    atomic_radii = AtomicRadii::Bragg,
+    center_kinds = None,
+    screening = false,
+    soa = false,
+))]
+fn molecular_grid<'py>(
+    py: Python<'py>,
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    radial_precision: f64,
+    min_num_angular_points: usize,
+    max_num_angular_points: usize,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    pruning: Pruning,
+    atomic_radii: AtomicRadii,
+    center_kinds: Option<Vec<CenterKind>>,
+    screening: bool,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let radial_schemes = molecule::lmg_schemes(
+        alpha_min,
+        alpha_max,
+        radial_precision,
+        center_coordinates_bohr.0.len(),
+    )?;
+    let mut builder = MolecularGridBuilder::new(radial_schemes)
+        .angular_points(min_num_angular_points, max_num_angular_points)
+        .hardness(hardness)
+        .partitioning(partitioning)
+        .pruning(pruning)
+        .atomic_radii(atomic_radii)
+        .screening(screening);
+    if let Some(center_kinds) = center_kinds {
+        builder = builder.center_kinds(center_kinds);
+    }
+    molecular_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+/// Like `molecular_grid` but with radial precision, angular points, and
+/// hardness taken from a named quality level for the element of each center.
+#[pyfunction]
+#[pyo3(signature = (
+    quality,
+    alpha_min,
+    alpha_max,
+    proton_charges,
+    center_coordinates_bohr,
+    partitioning = Partitioning::Becke,
+    soa = false,
+))]
+fn molecular_grid_quality<'py>(
+    py: Python<'py>,
+    quality: Quality,
+    alpha_min: Vec<HashMap<usize, f64>>,
+    alpha_max: Vec<f64>,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Coordinates,
+    partitioning: Partitioning,
+    soa: bool,
+) -> PyResult<PyObject> {
+    if soa {
+        let grid = py.allow_threads(|| {
+            molecule::molecular_grid_quality_soa(
+                quality,
+                alpha_min,
+                alpha_max,
+                &proton_charges,
+                &center_coordinates_bohr.0,
+                partitioning,
+            )
+        })?;
+        Ok(molecular_soa_arrays(py, grid).into_py(py))
+    } else {
+        let grid = py.allow_threads(|| {
+            molecule::molecular_grid_quality(
+                quality,
+                alpha_min,
+                alpha_max,
+                proton_charges,
+                center_coordinates_bohr.0,
+                partitioning,
+            )
+        })?;
+        Ok(molecular_grid_arrays(py, grid)?.into_py(py))
+    }
+}
+
+/// Like `molecular_grid` but with the radial grid and fixed pruning of a
+/// standard grid.
+#[pyfunction]
+#[pyo3(signature = (
+    grid,
+    proton_charges,
+    center_coordinates_bohr,
+    hardness = 3,
+    partitioning = Partitioning::Becke,
+    soa = false,
+))]
+fn standard_molecular_grid(
+    py: Python<'_>,
+    grid: StandardGrid,
+    proton_charges: Vec<i32>,
+    center_coordinates_bohr: Coordinates,
+    hardness: usize,
+    partitioning: Partitioning,
+    soa: bool,
+) -> PyResult<PyObject> {
+    let builder = MolecularGridBuilder::standard(grid, center_coordinates_bohr.0.len())
+        .hardness(hardness)
+        .partitioning(partitioning);
+    molecular_grid_output(
+        py,
+        &builder,
+        &proton_charges,
+        &center_coordinates_bohr.0,
+        soa,
+    )
+}
+
+#[pyfunction]
+fn angular_grid(py: Python<'_>, num_points: usize) -> PyResult<Grid<'_>> {
+    grid_arrays(py, lebedev::angular_grid(num_points)?)
+}
+
+#[pyfunction]
+fn radial_grid_kk(py: Python<'_>, num_points: usize) -> RadialGrid<'_> {
+    radial_grid_arrays(py, radial::radial_grid_kk(num_points))
+}
+
+#[pyfunction]
+fn radial_grid_ta(
+    py: Python<'_>,
+    num_points: usize,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'_>> {
+    Ok(radial_grid_arrays(
+        py,
+        radial::radial_grid_ta(num_points, proton_charge)?,
+    ))
+}
+
+#[pyfunction]
+fn radial_grid_mk(
+    py: Python<'_>,
+    num_points: usize,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'_>> {
+    Ok(radial_grid_arrays(
+        py,
+        radial::radial_grid_mk(num_points, proton_charge)?,
+    ))
+}
+
+#[pyfunction]
+fn radial_grid_em(
+    py: Python<'_>,
+    num_points: usize,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'_>> {
+    Ok(radial_grid_arrays(
+        py,
+        radial::radial_grid_em(num_points, proton_charge)?,
+    ))
+}
+
+#[pyfunction]
+fn radial_grid_becke(
+    py: Python<'_>,
+    num_points: usize,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'_>> {
+    Ok(radial_grid_arrays(
+        py,
+        radial::radial_grid_becke(num_points, proton_charge)?,
+    ))
+}
+
+#[pyfunction]
+fn radial_grid_lmg_bse<'py>(
+    py: Python<'py>,
+    basis_set: &str,
+    radial_precision: f64,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'py>> {
+    let grid = py.allow_threads(|| {
+        radial::radial_grid_lmg_bse(basis_set, radial_precision, proton_charge)
+    })?;
+    Ok(radial_grid_arrays(py, grid))
+}
+
+#[pyfunction]
+fn radial_grid_lmg(
+    py: Python<'_>,
+    alpha_min: HashMap<usize, f64>,
+    alpha_max: f64,
+    radial_precision: f64,
+    proton_charge: i32,
+) -> PyResult<RadialGrid<'_>> {
+    Ok(radial_grid_arrays(
+        py,
+        radial::radial_grid_lmg(alpha_min, alpha_max, radial_precision, proton_charge)?,
+    ))
+}
+
+/// Splits a grid into batches of at most `max_batch_size` points. Points of
+/// each center are sorted along a Hilbert curve and cut into consecutive
+/// pieces, so all batches except the last one of each center are full.
+/// Batches are ordered by center.
+#[pyfunction]
+#[pyo3(signature = (coordinates_bohr, weights, center_indices, max_batch_size = 128))]
+fn batch_grid(
+    py: Python<'_>,
+    coordinates_bohr: Coordinates,
+    weights: Floats,
+    center_indices: Vec<usize>,
+    max_batch_size: usize,
+) -> PyResult<Vec<Batch>> {
+    Ok(py.allow_threads(|| {
+        batch::batch_grid(
+            coordinates_bohr.0,
+            weights.0,
+            center_indices,
+            max_batch_size,
+        )
+    })?)
+}
+
+/// Removes all points whose weight is smaller in magnitude than `threshold`.
+#[pyfunction]
+#[pyo3(signature = (coordinates_bohr, weights, threshold, center_indices = None))]
+fn screen_weights(
+    py: Python<'_>,
+    coordinates_bohr: Coordinates,
+    weights: Floats,
+    threshold: f64,
+    center_indices: Option<Vec<usize>>,
+) -> PyResult<ScreenedGrid> {
+    Ok(py.allow_threads(|| {
+        weight_screening::screen_weights(coordinates_bohr.0, weights.0, threshold, center_indices)
+    })?)
+}
 
 #[pymodule]
 fn numgrid(m: &Bound<'_, PyModule>) -> PyResult<()> {
     m.add("__version__", env!("CARGO_PKG_VERSION"))?;
 
     m.add_function(wrap_pyfunction!(atom_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(atom_grid_batches, m)?)?;
     m.add_function(wrap_pyfunction!(atom_grid_bse, m)?)?;
+    m.add_function(wrap_pyfunction!(atom_grid_from_radial, m)?)?;
+    m.add_function(wrap_pyfunction!(atom_grid_gradient, m)?)?;
+    m.add_function(wrap_pyfunction!(atom_grid_quality, m)?)?;
+    m.add_function(wrap_pyfunction!(alpha_min_and_max, m)?)?;
     m.add_function(wrap_pyfunction!(angular_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(batch_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(grid_parameters, m)?)?;
+    m.add_function(wrap_pyfunction!(molecular_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(molecular_grid_quality, m)?)?;
+    m.add_function(wrap_pyfunction!(radial_grid_becke, m)?)?;
+    m.add_function(wrap_pyfunction!(radial_grid_em, m)?)?;
     m.add_function(wrap_pyfunction!(radial_grid_kk, m)?)?;
     m.add_function(wrap_pyfunction!(radial_grid_lmg, m)?)?;
     m.add_function(wrap_pyfunction!(radial_grid_lmg_bse, m)?)?;
+    m.add_function(wrap_pyfunction!(radial_grid_mk, m)?)?;
+    m.add_function(wrap_pyfunction!(radial_grid_ta, m)?)?;
+    m.add_function(wrap_pyfunction!(screen_weights, m)?)?;
+    m.add_function(wrap_pyfunction!(standard_atom_grid, m)?)?;
+    m.add_function(wrap_pyfunction!(standard_molecular_grid, m)?)?;
+
+    m.add_class::<Batch>()?;
+    m.add_class::<GridBatches>()?;
+    m.add_class::<ScreenedGrid>()?;
 
     Ok(())
 }
@@ -0,0 +1,71 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+/// Named accuracy levels which map to parameters of `atom_grid`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Quality {
+    Coarse,
+    Medium,
+    Fine,
+    UltraFine,
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for Quality {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        match name.to_lowercase().as_str() {
+            "coarse" => Ok(Quality::Coarse),
+            "medium" => Ok(Quality::Medium),
+            "fine" => Ok(Quality::Fine),
+            "ultrafine" => Ok(Quality::UltraFine),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown quality '{}', allowed are: 'coarse', 'medium', 'fine', 'ultrafine'",
+                name
+            ))),
+        }
+    }
+}
+
+/// Parameters of `atom_grid` for one quality level and element.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GridParameters {
+    pub radial_precision: f64,
+    pub min_num_angular_points: usize,
+    pub max_num_angular_points: usize,
+    pub hardness: usize,
+}
+
+impl Quality {
+    // elements beyond Ar get more angular points in the valence region
+    pub fn parameters(self, proton_charge: i32) -> GridParameters {
+        let heavy = proton_charge > 18;
+        let (radial_precision, min_num_angular_points, max_num_angular_points) = match self {
+            Quality::Coarse => (1.0e-6, 26, if heavy { 194 } else { 146 }),
+            Quality::Medium => (1.0e-8, 50, if heavy { 302 } else { 194 }),
+            Quality::Fine => (1.0e-12, 86, if heavy { 434 } else { 302 }),
+            Quality::UltraFine => (1.0e-14, 110, if heavy { 770 } else { 590 }),
+        };
+        GridParameters {
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            hardness: 3,
+        }
+    }
+}
+
+/// Parameters for a quality level and element as a tuple
+/// `(radial_precision, min_num_angular_points, max_num_angular_points, hardness)`.
+#[cfg_attr(feature = "python", pyfunction)]
+pub fn grid_parameters(quality: Quality, proton_charge: i32) -> (f64, usize, usize, usize) {
+    let p = quality.parameters(proton_charge);
+    (
+        p.radial_precision,
+        p.min_num_angular_points,
+        p.max_num_angular_points,
+        p.hardness,
+    )
+}
@@ -1,18 +1,86 @@
 #![allow(clippy::many_single_char_names)]
 
-use pyo3::prelude::*;
-
 use std::collections::HashMap;
 
 use crate::bragg;
 use crate::bse;
+use crate::error::NumgridError;
 use crate::parameters;
+use crate::standard_grids;
+use crate::standard_grids::StandardGrid;
 use statrs::function::gamma;
 
 #[cfg(test)]
 use crate::comparison;
 
-#[pyfunction]
+/// Radial quadrature together with its parameters.
+#[derive(Clone, Debug, PartialEq)]
+pub enum RadialScheme {
+    /// Lindh-Malmqvist-Gagliardi, range from the basis set exponents.
+    Lmg {
+        alpha_min: HashMap<usize, f64>,
+        alpha_max: f64,
+        radial_precision: f64,
+    },
+    /// Lindh-Malmqvist-Gagliardi with the exponents of a basis set, looked up
+    /// by name or file as in `radial_grid_lmg_bse`.
+    LmgBse {
+        basis_set: String,
+        radial_precision: f64,
+    },
+    /// Krack-Koster.
+    Kk { num_points: usize },
+    /// Treutler-Ahlrichs M4.
+    Ta { num_points: usize },
+    /// Mura-Knowles log3.
+    Mk { num_points: usize },
+    /// Murray-Handy-Laming Euler-Maclaurin.
+    Em { num_points: usize },
+    /// Becke Gauss-Chebyshev.
+    Becke { num_points: usize },
+    /// Radii and weights (including r^2) computed elsewhere.
+    Explicit { radii: Vec<f64>, weights: Vec<f64> },
+    /// Radial part of a standard grid.
+    Standard(StandardGrid),
+}
+
+impl RadialScheme {
+    pub fn grid(&self, proton_charge: i32) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+        match self {
+            RadialScheme::Lmg {
+                alpha_min,
+                alpha_max,
+                radial_precision,
+            } => radial_grid_lmg(
+                alpha_min.clone(),
+                *alpha_max,
+                *radial_precision,
+                proton_charge,
+            ),
+            RadialScheme::LmgBse {
+                basis_set,
+                radial_precision,
+            } => radial_grid_lmg_bse(basis_set, *radial_precision, proton_charge),
+            RadialScheme::Kk { num_points } => Ok(radial_grid_kk(*num_points)),
+            RadialScheme::Ta { num_points } => radial_grid_ta(*num_points, proton_charge),
+            RadialScheme::Mk { num_points } => radial_grid_mk(*num_points, proton_charge),
+            RadialScheme::Em { num_points } => radial_grid_em(*num_points, proton_charge),
+            RadialScheme::Becke { num_points } => radial_grid_becke(*num_points, proton_charge),
+            RadialScheme::Explicit { radii, weights } => {
+                if radii.len() != weights.len() {
+                    return Err(NumgridError::InvalidInput(format!(
+                        "{} radii but {} radial weights",
+                        radii.len(),
+                        weights.len()
+                    )));
+                }
+                Ok((radii.clone(), weights.clone()))
+            }
+            RadialScheme::Standard(grid) => standard_grids::radial_grid(*grid, proton_charge),
+        }
+    }
+}
+
 pub fn radial_grid_kk(num_points: usize) -> (Vec<f64>, Vec<f64>) {
     let n = num_points as i32;
     let mut rws: Vec<_> = (1..=n).map(|i| kk_r_w(i, n)).collect();
@@ -47,22 +115,22 @@ fn test_radial_grid_kk() {
     ));
     assert!(comparison::floats_are_same(
         rs[97],
-        22.522899235303480,
+        22.52289923530348,
         1.0e-15
     ));
     assert!(comparison::floats_are_same(
         rs[0],
-        7.4914976443367854e-9,
+        7.491497644336785e-9,
         1.0e-15
     ));
     assert!(comparison::floats_are_same(
         ws[98],
-        5462.4446669497620,
+        5462.444666949762,
         1.0e-15
     ));
     assert!(comparison::floats_are_same(
         ws[97],
-        1828.2535433191961,
+        1828.253543319196,
         1.0e-15
     ));
     assert!(comparison::floats_are_same(
@@ -72,48 +140,420 @@ fn test_radial_grid_kk() {
     ));
 }
 
-#[pyfunction]
+pub fn radial_grid_ta(
+    num_points: usize,
+    proton_charge: i32,
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    let xi = ta_xi(proton_charge)?;
+    let n = num_points as i32;
+    let mut rws: Vec<_> = (1..=n).map(|i| ta_r_w(i, n, xi)).collect();
+    rws.reverse();
+    Ok(rws.iter().cloned().unzip())
+}
+
+// M4 mapping, JCP 102, 346 (1995), eq. 19 with alpha = 0.6,
+// combined with Chebyshev quadrature of the second kind
+fn ta_r_w(i: i32, n: i32, xi: f64) -> (f64, f64) {
+    let pi = std::f64::consts::PI;
+    let alpha = 0.6;
+
+    let angle = ((i as f64) * pi) / ((n + 1) as f64);
+    let s = angle.sin();
+    let x = angle.cos();
+    let f = xi / 2.0_f64.ln();
+    let p = (1.0 + x).powf(alpha);
+    let l = (2.0 / (1.0 - x)).ln();
+    let r = f * p * l;
+    let dr = f * (alpha * p * l / (1.0 + x) + p / (1.0 - x));
+    let w = r * r * dr * s * pi / ((n + 1) as f64);
+
+    (r, w)
+}
+
+// JCP 102, 346 (1995), table 1; the paper gives values up to Kr,
+// for heavier elements we use 1.0
+pub(crate) fn ta_xi(charge: i32) -> Result<f64, NumgridError> {
+    let xi = match charge {
+        1 => 0.8,
+        2 => 0.9,
+        3 => 1.8,
+        4 => 1.4,
+        5 => 1.3,
+        6 => 1.1,
+        7..=10 => 0.9,
+        11 => 1.4,
+        12 => 1.3,
+        13 => 1.3,
+        14 => 1.2,
+        15 => 1.1,
+        16..=18 => 1.0,
+        19 => 1.5,
+        20 => 1.4,
+        21 => 1.3,
+        22..=27 => 1.2,
+        28..=31 => 1.1,
+        32 => 1.0,
+        33..=36 => 0.9,
+        37..=118 => 1.0,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+
+    Ok(xi)
+}
+
+#[test]
+fn test_radial_grid_ta() {
+    let (rs, ws) = radial_grid_ta(99, 8).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        16.34663651394502,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[97],
+        13.613187490029215,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[0],
+        3.3236484483506914e-6,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[98],
+        1052.8238303917815,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[97],
+        365.8744517590902,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[0],
+        1.1748785899104042e-16,
+        1.0e-14
+    ));
+
+    // integral of exp(-r^2) r^2 from 0 to infinity
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(r, w)| w * (-r * r).exp())
+        .sum();
+    let pi = std::f64::consts::PI;
+    assert!(comparison::floats_are_same(
+        integral,
+        pi.sqrt() / 4.0,
+        1.0e-12
+    ));
+
+    assert!(radial_grid_ta(99, 0).is_err());
+}
+
+/// Mura-Knowles log3 grid with alpha=7 for groups 1 and 2 and alpha=5
+/// otherwise, Mura and Knowles, JCP 104, 9848 (1996).
+pub fn radial_grid_mk(
+    num_points: usize,
+    proton_charge: i32,
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    let alpha = mk_alpha(proton_charge)?;
+    let n = num_points as i32;
+    Ok((1..=n).map(|i| mk_r_w(i, n, alpha)).unzip())
+}
+
+// log3 grid, JCP 104, 9848 (1996), eqs. 9 and 10
+fn mk_r_w(i: i32, n: i32, alpha: f64) -> (f64, f64) {
+    let x = (i as f64) / ((n + 1) as f64);
+    let x3 = x * x * x;
+    let r = -alpha * (1.0 - x3).ln();
+    let w = r * r * 3.0 * alpha * x * x / ((1.0 - x3) * ((n + 1) as f64));
+
+    (r, w)
+}
+
+// JCP 104, 9848 (1996): 7 for alkali and alkaline earth metals, 5 otherwise
+fn mk_alpha(charge: i32) -> Result<f64, NumgridError> {
+    match charge {
+        3 | 4 | 11 | 12 | 19 | 20 | 37 | 38 | 55 | 56 | 87 | 88 => Ok(7.0),
+        1..=118 => Ok(5.0),
+        _ => Err(NumgridError::UnsupportedElement(charge)),
+    }
+}
+
+#[test]
+fn test_radial_grid_mk() {
+    let (rs, ws) = radial_grid_mk(99, 8).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        17.582872818533183,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[97],
+        14.167386894551745,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[0],
+        5.0000025001454455e-6,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[98],
+        1530.27768743467,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[97],
+        491.6844901297976,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[0],
+        3.750007500226606e-16,
+        1.0e-14
+    ));
+
+    // integral of exp(-r^2) r^2 from 0 to infinity
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(r, w)| w * (-r * r).exp())
+        .sum();
+    let pi = std::f64::consts::PI;
+    assert!(comparison::floats_are_same(
+        integral,
+        pi.sqrt() / 4.0,
+        1.0e-12
+    ));
+
+    // lithium uses the larger scale factor
+    let (rs, _) = radial_grid_mk(99, 3).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        24.616021945946457,
+        1.0e-14
+    ));
+
+    assert!(radial_grid_mk(99, 0).is_err());
+}
+
+// Euler-Maclaurin scheme of Murray, Handy, and Laming,
+// Mol. Phys. 78, 997 (1993), with m = 2 and the Bragg radius as scale
+pub fn radial_grid_em(
+    num_points: usize,
+    proton_charge: i32,
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    let alpha = bragg::get_bragg_angstrom(proton_charge)? / 0.529177249;
+    Ok(em_grid(num_points, alpha))
+}
+
+pub(crate) fn em_grid(num_points: usize, alpha: f64) -> (Vec<f64>, Vec<f64>) {
+    let n = num_points as i32;
+    (1..=n).map(|i| em_r_w(i, n, alpha)).unzip()
+}
+
+fn em_r_w(i: i32, n: i32, alpha: f64) -> (f64, f64) {
+    let x = (i as f64) / ((n + 1) as f64);
+    let r = alpha * x * x / ((1.0 - x) * (1.0 - x));
+    let w = r * r * 2.0 * alpha * x / ((1.0 - x).powi(3) * ((n + 1) as f64));
+
+    (r, w)
+}
+
+#[test]
+fn test_radial_grid_em() {
+    let (rs, ws) = radial_grid_em(99, 8).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        11112.722648437197,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[97],
+        2722.3392591467914,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[0],
+        0.00011568570484107276,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[98],
+        2772402150973.3,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[97],
+        20587360193.901806,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[0],
+        3.127763389740251e-12,
+        1.0e-14
+    ));
+
+    // integral of exp(-r^2) r^2 from 0 to infinity
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(r, w)| w * (-r * r).exp())
+        .sum();
+    let pi = std::f64::consts::PI;
+    assert!(comparison::floats_are_same(
+        integral,
+        pi.sqrt() / 4.0,
+        1.0e-12
+    ));
+}
+
+// Gauss-Chebyshev scheme of the second kind, JCP 88, 2547 (1988), eq. 25;
+// rm is half the Bragg radius except for hydrogen
+pub fn radial_grid_becke(
+    num_points: usize,
+    proton_charge: i32,
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    let mut rm = bragg::get_bragg_angstrom(proton_charge)? / 0.529177249;
+    if proton_charge > 1 {
+        rm *= 0.5;
+    }
+    let n = num_points as i32;
+    let mut rws: Vec<_> = (1..=n).map(|i| becke_r_w(i, n, rm)).collect();
+    rws.reverse();
+    Ok(rws.iter().cloned().unzip())
+}
+
+fn becke_r_w(i: i32, n: i32, rm: f64) -> (f64, f64) {
+    let pi = std::f64::consts::PI;
+
+    let angle = ((i as f64) * pi) / ((n + 1) as f64);
+    let s = angle.sin();
+    let x = angle.cos();
+    let r = rm * (1.0 + x) / (1.0 - x);
+    let dr = 2.0 * rm / ((1.0 - x) * (1.0 - x));
+    let w = r * r * dr * s * pi / ((n + 1) as f64);
+
+    (r, w)
+}
+
+#[test]
+fn test_radial_grid_becke() {
+    let (rs, ws) = radial_grid_becke(99, 8).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        2297.2533511983147,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[97],
+        574.0299138773339,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        rs[0],
+        0.00013990437228192685,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[98],
+        24250914352.560516,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[97],
+        189273305.51788598,
+        1.0e-14
+    ));
+    assert!(comparison::floats_are_same(
+        ws[0],
+        5.477662855081123e-12,
+        1.0e-14
+    ));
+
+    // integral of exp(-r^2) r^2 from 0 to infinity
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(r, w)| w * (-r * r).exp())
+        .sum();
+    let pi = std::f64::consts::PI;
+    assert!(comparison::floats_are_same(
+        integral,
+        pi.sqrt() / 4.0,
+        1.0e-12
+    ));
+
+    // hydrogen uses the full Bragg radius
+    let (rs, _) = radial_grid_becke(99, 1).unwrap();
+    assert!(comparison::floats_are_same(
+        rs[98],
+        2680.1289097313665,
+        1.0e-14
+    ));
+}
+
 pub fn radial_grid_lmg_bse(
     basis_set: &str,
     radial_precision: f64,
     proton_charge: i32,
-) -> (Vec<f64>, Vec<f64>) {
-    let (alpha_min, alpha_max) = bse::ang_min_and_max(basis_set, proton_charge as usize);
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    let (alpha_min, alpha_max) = bse::ang_min_and_max(basis_set, proton_charge as usize)?;
 
     radial_grid_lmg(alpha_min, alpha_max, radial_precision, proton_charge)
 }
 
-#[pyfunction]
 pub fn radial_grid_lmg(
     alpha_min: HashMap<usize, f64>,
     alpha_max: f64,
     radial_precision: f64,
     proton_charge: i32,
-) -> (Vec<f64>, Vec<f64>) {
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    if radial_precision <= 0.0 || radial_precision >= 1.0 {
+        return Err(NumgridError::InvalidInput(format!(
+            "radial_precision must be between 0 and 1, got {}",
+            radial_precision
+        )));
+    }
+    if alpha_max <= 0.0 {
+        return Err(NumgridError::InvalidInput(format!(
+            "alpha_max must be positive, got {}",
+            alpha_max
+        )));
+    }
+
+    let r_bragg = bragg::get_bragg_angstrom(proton_charge)?;
+
     // factor 2.0 to match DIRAC code
     let r_inner = get_r_inner(radial_precision, alpha_max * 2.0);
 
-    let mut h = std::f64::MAX;
+    let mut h = f64::MAX;
     let mut r_outer: f64 = 0.0;
 
     // we need alpha_min sorted by l
     // at this point not sure why ... need to check again the literature
     let mut v: Vec<_> = alpha_min.into_iter().collect();
-    v.sort_by(|x, y| x.0.cmp(&y.0));
+    v.sort_by_key(|x| x.0);
 
     for (l, a) in v {
         if a > 0.0 {
-            r_outer = r_outer.max(get_r_outer(
-                radial_precision,
-                a,
-                l,
-                4.0 * bragg::get_bragg_angstrom(proton_charge),
-            ));
-            assert!(r_outer > r_inner);
+            r_outer = r_outer.max(get_r_outer(radial_precision, a, l, 4.0 * r_bragg));
+            if r_outer <= r_inner {
+                return Err(NumgridError::InvalidInput(format!(
+                    "outer radius {} is not larger than inner radius {}, check alpha_min and alpha_max",
+                    r_outer, r_inner
+                )));
+            }
             h = h.min(get_h(radial_precision, l, 0.1 * (r_outer - r_inner)));
         }
     }
-    assert!(r_outer > h);
+    if r_outer <= h {
+        return Err(NumgridError::InvalidInput(
+            "alpha_min must contain at least one positive exponent".to_string(),
+        ));
+    }
 
     let c = r_inner / (h.exp() - 1.0);
     let num_points = ((1.0 + (r_outer / c)).ln() / h) as usize;
@@ -127,7 +567,19 @@ pub fn radial_grid_lmg(
         ws.push((r + c) * r * r * h);
     }
 
-    (rs, ws)
+    Ok((rs, ws))
+}
+
+#[test]
+fn test_radial_grid_lmg_invalid_input() {
+    let mut alpha_min = HashMap::new();
+    alpha_min.insert(0, 0.3023);
+
+    assert!(radial_grid_lmg(alpha_min.clone(), 11720.0, 1.0e-12, 8).is_ok());
+    assert!(radial_grid_lmg(alpha_min.clone(), 11720.0, 0.0, 8).is_err());
+    assert!(radial_grid_lmg(alpha_min.clone(), -1.0, 1.0e-12, 8).is_err());
+    assert!(radial_grid_lmg(alpha_min, 11720.0, 1.0e-12, 0).is_err());
+    assert!(radial_grid_lmg(HashMap::new(), 11720.0, 1.0e-12, 8).is_err());
 }
 
 // TCA 106, 178 (2001), eq. 25
@@ -160,7 +612,7 @@ fn test_get_r_inner() {
 // TCA 106, 178 (2001), eq. 19
 fn get_r_outer(max_error: f64, alpha_outer: f64, l: usize, guess: f64) -> f64 {
     let m = (2 * l) as f64;
-    let mut r_old = std::f64::MAX;
+    let mut r_old = f64::MAX;
     let mut step = 0.5;
     let mut sign = 1.0;
     let mut r = guess;
@@ -229,7 +681,7 @@ fn test_get_r_outer() {
 // TCA 106, 178 (2001), eqs. 17 and 18
 fn get_h(max_error: f64, l: usize, guess: f64) -> f64 {
     let m = (2 * l) as f64;
-    let mut h_old = std::f64::MAX;
+    let mut h_old = f64::MAX;
     let mut h = guess;
     let mut step = 0.1 * guess;
     let mut sign = -1.0;
@@ -0,0 +1,487 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::bragg;
+use crate::error::NumgridError;
+use crate::radial;
+
+/// Atomic radii used for the Becke size adjustments and for the pruning.
+#[derive(Clone, Debug, PartialEq)]
+pub enum AtomicRadii {
+    /// Bragg-Slater radii as in DIRAC15, see `bragg.rs`. This is the default.
+    Bragg,
+    /// Covalent radii of Cordero et al., Dalton Trans. 2832 (2008).
+    Cordero,
+    /// Empirical radii of Slater, JCP 41, 3199 (1964), without the
+    /// modifications of the Bragg table.
+    Slater,
+    /// Scale factors xi (bohr) of the Treutler-Ahlrichs radial grid,
+    /// JCP 102, 346 (1995), for H-Kr.
+    Treutler,
+    /// Valence bond radii of the universal force field, Rappé et al.,
+    /// JACS 114, 10024 (1992), for H-Lr.
+    Uff,
+    /// One radius (bohr) for each center.
+    Explicit(Vec<f64>),
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for AtomicRadii {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(name) = ob.extract::<String>() {
+            return match name.to_lowercase().as_str() {
+                "bragg" => Ok(AtomicRadii::Bragg),
+                "cordero" => Ok(AtomicRadii::Cordero),
+                "slater" => Ok(AtomicRadii::Slater),
+                "treutler" => Ok(AtomicRadii::Treutler),
+                "uff" => Ok(AtomicRadii::Uff),
+                _ => Err(PyValueError::new_err(format!(
+                    "unknown radii '{}', allowed are: 'bragg', 'cordero', 'slater', 'treutler', 'uff', or a list of radii",
+                    name
+                ))),
+            };
+        }
+        Ok(AtomicRadii::Explicit(ob.extract()?))
+    }
+}
+
+impl AtomicRadii {
+    // radii of the centers at the given indices
+    pub(crate) fn select(
+        &self,
+        indices: &[usize],
+        num_centers: usize,
+    ) -> Result<AtomicRadii, NumgridError> {
+        match self {
+            AtomicRadii::Explicit(radii) => {
+                if radii.len() != num_centers {
+                    return Err(NumgridError::InvalidInput(format!(
+                        "{} radii but {} centers",
+                        radii.len(),
+                        num_centers
+                    )));
+                }
+                Ok(AtomicRadii::Explicit(
+                    indices.iter().map(|&i| radii[i]).collect(),
+                ))
+            }
+            _ => Ok(self.clone()),
+        }
+    }
+
+    /// Radius in angstrom for each center.
+    pub fn radii_angstrom(&self, proton_charges: &[i32]) -> Result<Vec<f64>, NumgridError> {
+        match self {
+            AtomicRadii::Bragg => proton_charges
+                .iter()
+                .map(|&charge| bragg::get_bragg_angstrom(charge))
+                .collect(),
+            AtomicRadii::Cordero => proton_charges
+                .iter()
+                .map(|&charge| get_cordero_angstrom(charge))
+                .collect(),
+            AtomicRadii::Slater => proton_charges
+                .iter()
+                .map(|&charge| get_slater_angstrom(charge))
+                .collect(),
+            AtomicRadii::Treutler => proton_charges
+                .iter()
+                .map(|&charge| get_treutler_angstrom(charge))
+                .collect(),
+            AtomicRadii::Uff => proton_charges
+                .iter()
+                .map(|&charge| get_uff_angstrom(charge))
+                .collect(),
+            AtomicRadii::Explicit(radii) => {
+                if radii.len() != proton_charges.len() {
+                    return Err(NumgridError::InvalidInput(format!(
+                        "{} radii but {} centers",
+                        radii.len(),
+                        proton_charges.len()
+                    )));
+                }
+                if radii.iter().any(|&r| r <= 0.0) {
+                    return Err(NumgridError::InvalidInput(
+                        "radii have to be positive".to_string(),
+                    ));
+                }
+                Ok(radii.iter().map(|r| r * 0.529177249).collect())
+            }
+        }
+    }
+}
+
+// Dalton Trans. 2832 (2008), table 2; low-spin values for Mn, Fe, and Co
+// and sp3 for C
+fn get_cordero_angstrom(charge: i32) -> Result<f64, NumgridError> {
+    let radius = match charge {
+        1 => 0.31,
+        2 => 0.28,
+        3 => 1.28,
+        4 => 0.96,
+        5 => 0.84,
+        6 => 0.76,
+        7 => 0.71,
+        8 => 0.66,
+        9 => 0.57,
+        10 => 0.58,
+        11 => 1.66,
+        12 => 1.41,
+        13 => 1.21,
+        14 => 1.11,
+        15 => 1.07,
+        16 => 1.05,
+        17 => 1.02,
+        18 => 1.06,
+        19 => 2.03,
+        20 => 1.76,
+        21 => 1.70,
+        22 => 1.60,
+        23 => 1.53,
+        24 => 1.39,
+        25 => 1.39,
+        26 => 1.32,
+        27 => 1.26,
+        28 => 1.24,
+        29 => 1.32,
+        30 => 1.22,
+        31 => 1.22,
+        32 => 1.20,
+        33 => 1.19,
+        34 => 1.20,
+        35 => 1.20,
+        36 => 1.16,
+        37 => 2.20,
+        38 => 1.95,
+        39 => 1.90,
+        40 => 1.75,
+        41 => 1.64,
+        42 => 1.54,
+        43 => 1.47,
+        44 => 1.46,
+        45 => 1.42,
+        46 => 1.39,
+        47 => 1.45,
+        48 => 1.44,
+        49 => 1.42,
+        50 => 1.39,
+        51 => 1.39,
+        52 => 1.38,
+        53 => 1.39,
+        54 => 1.40,
+        55 => 2.44,
+        56 => 2.15,
+        57 => 2.07,
+        58 => 2.04,
+        59 => 2.03,
+        60 => 2.01,
+        61 => 1.99,
+        62 => 1.98,
+        63 => 1.98,
+        64 => 1.96,
+        65 => 1.94,
+        66 => 1.92,
+        67 => 1.92,
+        68 => 1.89,
+        69 => 1.90,
+        70 => 1.87,
+        71 => 1.87,
+        72 => 1.75,
+        73 => 1.70,
+        74 => 1.62,
+        75 => 1.51,
+        76 => 1.44,
+        77 => 1.41,
+        78 => 1.36,
+        79 => 1.36,
+        80 => 1.32,
+        81 => 1.45,
+        82 => 1.46,
+        83 => 1.48,
+        84 => 1.40,
+        85 => 1.50,
+        86 => 1.50,
+        87 => 2.60,
+        88 => 2.21,
+        89 => 2.15,
+        90 => 2.06,
+        91 => 2.00,
+        92 => 1.96,
+        93 => 1.90,
+        94 => 1.87,
+        95 => 1.80,
+        96 => 1.69,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+
+    Ok(radius)
+}
+
+// JCP 41, 3199 (1964), table I; there are no values for the noble gases,
+// At, and Fr, and none beyond Am
+fn get_slater_angstrom(charge: i32) -> Result<f64, NumgridError> {
+    let radius = match charge {
+        1 => 0.25,
+        3 => 1.45,
+        4 => 1.05,
+        5 => 0.85,
+        6 => 0.70,
+        7 => 0.65,
+        8 => 0.60,
+        9 => 0.50,
+        11 => 1.80,
+        12 => 1.50,
+        13 => 1.25,
+        14 => 1.10,
+        15 => 1.00,
+        16 => 1.00,
+        17 => 1.00,
+        19 => 2.20,
+        20 => 1.80,
+        21 => 1.60,
+        22 => 1.40,
+        23 => 1.35,
+        24 => 1.40,
+        25 => 1.40,
+        26 => 1.40,
+        27 => 1.35,
+        28 => 1.35,
+        29 => 1.35,
+        30 => 1.35,
+        31 => 1.30,
+        32 => 1.25,
+        33 => 1.15,
+        34 => 1.15,
+        35 => 1.15,
+        37 => 2.35,
+        38 => 2.00,
+        39 => 1.80,
+        40 => 1.55,
+        41 => 1.45,
+        42 => 1.45,
+        43 => 1.35,
+        44 => 1.30,
+        45 => 1.35,
+        46 => 1.40,
+        47 => 1.60,
+        48 => 1.55,
+        49 => 1.55,
+        50 => 1.45,
+        51 => 1.45,
+        52 => 1.40,
+        53 => 1.40,
+        55 => 2.60,
+        56 => 2.15,
+        57 => 1.95,
+        58 => 1.85,
+        59 => 1.85,
+        60 => 1.85,
+        61 => 1.85,
+        62 => 1.85,
+        63 => 1.85,
+        64 => 1.80,
+        65 => 1.75,
+        66 => 1.75,
+        67 => 1.75,
+        68 => 1.75,
+        69 => 1.75,
+        70 => 1.75,
+        71 => 1.75,
+        72 => 1.55,
+        73 => 1.45,
+        74 => 1.35,
+        75 => 1.35,
+        76 => 1.30,
+        77 => 1.35,
+        78 => 1.35,
+        79 => 1.35,
+        80 => 1.50,
+        81 => 1.90,
+        82 => 1.80,
+        83 => 1.60,
+        84 => 1.90,
+        88 => 2.15,
+        89 => 1.95,
+        90 => 1.80,
+        91 => 1.80,
+        92 => 1.75,
+        93 => 1.75,
+        94 => 1.75,
+        95 => 1.75,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+
+    Ok(radius)
+}
+
+// JCP 102, 346 (1995), table 1, which ends at Kr
+fn get_treutler_angstrom(charge: i32) -> Result<f64, NumgridError> {
+    if !(1..=36).contains(&charge) {
+        return Err(NumgridError::UnsupportedElement(charge));
+    }
+
+    Ok(radial::ta_xi(charge)? * 0.529177249)
+}
+
+// JACS 114, 10024 (1992), table 1, r_I of the first atom type of each element
+// (e.g. H_, C_3, N_3, O_3)
+fn get_uff_angstrom(charge: i32) -> Result<f64, NumgridError> {
+    let radius = match charge {
+        1 => 0.354,
+        2 => 0.849,
+        3 => 1.336,
+        4 => 1.074,
+        5 => 0.838,
+        6 => 0.757,
+        7 => 0.700,
+        8 => 0.658,
+        9 => 0.668,
+        10 => 0.920,
+        11 => 1.539,
+        12 => 1.421,
+        13 => 1.244,
+        14 => 1.117,
+        15 => 1.101,
+        16 => 1.064,
+        17 => 1.044,
+        18 => 1.032,
+        19 => 1.953,
+        20 => 1.761,
+        21 => 1.513,
+        22 => 1.412,
+        23 => 1.402,
+        24 => 1.345,
+        25 => 1.382,
+        26 => 1.270,
+        27 => 1.241,
+        28 => 1.164,
+        29 => 1.302,
+        30 => 1.193,
+        31 => 1.260,
+        32 => 1.197,
+        33 => 1.211,
+        34 => 1.190,
+        35 => 1.192,
+        36 => 1.147,
+        37 => 2.260,
+        38 => 2.052,
+        39 => 1.698,
+        40 => 1.564,
+        41 => 1.473,
+        42 => 1.467,
+        43 => 1.322,
+        44 => 1.478,
+        45 => 1.332,
+        46 => 1.338,
+        47 => 1.386,
+        48 => 1.403,
+        49 => 1.459,
+        50 => 1.398,
+        51 => 1.407,
+        52 => 1.386,
+        53 => 1.382,
+        54 => 1.267,
+        55 => 2.570,
+        56 => 2.277,
+        57 => 1.943,
+        58 => 1.841,
+        59 => 1.823,
+        60 => 1.816,
+        61 => 1.801,
+        62 => 1.780,
+        63 => 1.771,
+        64 => 1.735,
+        65 => 1.732,
+        66 => 1.710,
+        67 => 1.696,
+        68 => 1.673,
+        69 => 1.660,
+        70 => 1.637,
+        71 => 1.671,
+        72 => 1.611,
+        73 => 1.511,
+        74 => 1.392,
+        75 => 1.372,
+        76 => 1.372,
+        77 => 1.371,
+        78 => 1.364,
+        79 => 1.262,
+        80 => 1.340,
+        81 => 1.518,
+        82 => 1.459,
+        83 => 1.512,
+        84 => 1.500,
+        85 => 1.545,
+        86 => 1.420,
+        87 => 2.880,
+        88 => 2.512,
+        89 => 1.983,
+        90 => 1.721,
+        91 => 1.711,
+        92 => 1.684,
+        93 => 1.666,
+        94 => 1.657,
+        95 => 1.660,
+        96 => 1.801,
+        97 => 1.761,
+        98 => 1.750,
+        99 => 1.724,
+        100 => 1.712,
+        101 => 1.689,
+        102 => 1.679,
+        103 => 1.698,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+
+    Ok(radius)
+}
+
+#[test]
+fn test_radii_angstrom() {
+    let radii = AtomicRadii::Cordero.radii_angstrom(&[8, 1, 1]).unwrap();
+    assert_eq!(radii, vec![0.66, 0.31, 0.31]);
+
+    let radii = AtomicRadii::Bragg.radii_angstrom(&[8, 1]).unwrap();
+    assert_eq!(radii, vec![0.60, 0.35]);
+
+    let radii = AtomicRadii::Explicit(vec![1.0, 2.0]);
+    assert_eq!(
+        radii.radii_angstrom(&[8, 1]).unwrap(),
+        vec![0.529177249, 2.0 * 0.529177249]
+    );
+    assert!(radii.radii_angstrom(&[8, 1, 1]).is_err());
+    assert!(AtomicRadii::Explicit(vec![1.0, 0.0])
+        .radii_angstrom(&[8, 1])
+        .is_err());
+
+    assert!(AtomicRadii::Cordero.radii_angstrom(&[97]).is_err());
+
+    // values from the table in the paper, where they differ from the Bragg
+    // radii (H, Re, Pb), and gaps in the table
+    let radii = AtomicRadii::Slater
+        .radii_angstrom(&[1, 6, 75, 82, 95])
+        .unwrap();
+    assert_eq!(radii, vec![0.25, 0.70, 1.35, 1.80, 1.75]);
+    for charge in [2, 10, 18, 36, 54, 85, 86, 87, 96] {
+        assert!(AtomicRadii::Slater.radii_angstrom(&[charge]).is_err());
+    }
+
+    // xi of H, Li, C, Fe, and Kr in table 1 of the paper, in bohr
+    let radii = AtomicRadii::Treutler
+        .radii_angstrom(&[1, 3, 6, 26, 36])
+        .unwrap();
+    for (r, xi) in radii.iter().zip([0.8, 1.8, 1.1, 1.2, 0.9]) {
+        assert_eq!(*r, xi * 0.529177249);
+    }
+    assert!(AtomicRadii::Treutler.radii_angstrom(&[37]).is_err());
+
+    // r_I of H_, C_3, O_3, Fe3+2, I_, U_6+4, and Lw6+3 in table 1 of the paper
+    let radii = AtomicRadii::Uff
+        .radii_angstrom(&[1, 6, 8, 26, 53, 92, 103])
+        .unwrap();
+    assert_eq!(radii, vec![0.354, 0.757, 0.658, 1.270, 1.382, 1.684, 1.698]);
+    assert!(AtomicRadii::Uff.radii_angstrom(&[0]).is_err());
+    assert!(AtomicRadii::Uff.radii_angstrom(&[104]).is_err());
+}
@@ -0,0 +1,131 @@
+#[cfg(feature = "python")]
+use pyo3::exceptions::PyValueError;
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::error::NumgridError;
+use crate::radial;
+
+#[cfg(test)]
+use crate::comparison;
+
+/// Standard pruned grids with fixed radial and angular point counts.
+// SG-0 and SG-2/SG-3 need per-element partition tables from their papers
+// which have not been added, see the README
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum StandardGrid {
+    /// Gill, Johnson, Pople, CPL 209, 506 (1993), defined for H-Ar.
+    Sg1,
+}
+
+#[cfg(feature = "python")]
+impl<'py> FromPyObject<'py> for StandardGrid {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let name: String = ob.extract()?;
+        match name.to_lowercase().replace('-', "").as_str() {
+            "sg1" => Ok(StandardGrid::Sg1),
+            _ => Err(PyValueError::new_err(format!(
+                "unknown standard grid '{}', allowed are: 'sg1'",
+                name
+            ))),
+        }
+    }
+}
+
+const SG1_NUM_RADIAL_POINTS: usize = 50;
+
+// CPL 209, 506 (1993), table 1, in bohr
+fn sg1_radius(charge: i32) -> Result<f64, NumgridError> {
+    let radius = match charge {
+        1 => 1.0000,
+        2 => 0.5882,
+        3 => 3.0769,
+        4 => 2.0513,
+        5 => 1.5385,
+        6 => 1.2308,
+        7 => 1.0256,
+        8 => 0.8791,
+        9 => 0.7692,
+        10 => 0.6838,
+        11 => 4.0909,
+        12 => 3.1579,
+        13 => 2.5714,
+        14 => 2.1687,
+        15 => 1.8750,
+        16 => 1.6514,
+        17 => 1.4754,
+        18 => 1.3333,
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+    Ok(radius)
+}
+
+// CPL 209, 506 (1993), table 2: region boundaries in units of the atomic
+// radius and the number of angular points in each of the five regions
+fn sg1_regions(charge: i32) -> Result<([f64; 4], [usize; 5]), NumgridError> {
+    let alphas = match charge {
+        1..=2 => [0.25, 0.5, 1.0, 4.5],
+        3..=10 => [0.1667, 0.5, 0.9, 3.5],
+        11..=18 => [0.1, 0.4, 0.8, 2.5],
+        _ => return Err(NumgridError::UnsupportedElement(charge)),
+    };
+    Ok((alphas, [6, 38, 86, 194, 86]))
+}
+
+pub fn radial_grid(
+    grid: StandardGrid,
+    proton_charge: i32,
+) -> Result<(Vec<f64>, Vec<f64>), NumgridError> {
+    match grid {
+        // Euler-Maclaurin with m = 2 and the SG-1 radius as scale
+        StandardGrid::Sg1 => Ok(radial::em_grid(
+            SG1_NUM_RADIAL_POINTS,
+            sg1_radius(proton_charge)?,
+        )),
+    }
+}
+
+pub fn num_angular_points(
+    grid: StandardGrid,
+    proton_charge: i32,
+    r: f64,
+) -> Result<usize, NumgridError> {
+    match grid {
+        StandardGrid::Sg1 => {
+            let radius = sg1_radius(proton_charge)?;
+            let (alphas, num_points) = sg1_regions(proton_charge)?;
+            let region = alphas.iter().filter(|&&a| r >= a * radius).count();
+            Ok(num_points[region])
+        }
+    }
+}
+
+#[test]
+fn test_sg1() {
+    // carbon, CPL 209, 506 (1993), eq. 3
+    let (rs, ws) = radial_grid(StandardGrid::Sg1, 6).unwrap();
+    assert_eq!(rs.len(), 50);
+    let r = 1.2308;
+    let n = 50.0;
+    for (i, (&ri, &wi)) in rs.iter().zip(ws.iter()).enumerate() {
+        let i = (i + 1) as f64;
+        assert!(comparison::floats_are_same(
+            ri,
+            r * i * i / ((n + 1.0 - i) * (n + 1.0 - i)),
+            1.0e-14
+        ));
+        assert!(comparison::floats_are_same(
+            wi,
+            2.0 * r.powi(3) * (n + 1.0) * i.powi(5) / (n + 1.0 - i).powi(7),
+            1.0e-12
+        ));
+    }
+
+    assert_eq!(num_angular_points(StandardGrid::Sg1, 6, 0.1).unwrap(), 6);
+    assert_eq!(num_angular_points(StandardGrid::Sg1, 6, 0.5).unwrap(), 38);
+    assert_eq!(num_angular_points(StandardGrid::Sg1, 6, 1.0).unwrap(), 86);
+    assert_eq!(num_angular_points(StandardGrid::Sg1, 6, 2.0).unwrap(), 194);
+    assert_eq!(num_angular_points(StandardGrid::Sg1, 6, 5.0).unwrap(), 86);
+
+    assert!(radial_grid(StandardGrid::Sg1, 19).is_err());
+}
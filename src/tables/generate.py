@@ -67,14 +67,14 @@ def write_tables(coordinates, ws):
     with open("coordinates.rs", "w") as f:
         f.write("#![allow(clippy::unreadable_literal)]\n\n")
         f.write("const F: f64 = std::f64::consts::FRAC_1_SQRT_2;\n\n")
-        f.write(f"pub const COORDINATES: [(f64, f64, f64); {len(coordinates)}] = [\n")
+        f.write(f"pub static COORDINATES: [(f64, f64, f64); {len(coordinates)}] = [\n")
         for (x, y, z) in coordinates:
             f.write(f"    ({x}, {y}, {z}),\n")
         f.write(f"];\n")
 
     with open("weights.rs", "w") as f:
         f.write("#![allow(clippy::unreadable_literal)]\n\n")
-        f.write(f"pub const WEIGHTS: [f64; {len(ws)}] = [\n")
+        f.write(f"pub static WEIGHTS: [f64; {len(ws)}] = [\n")
         for w in ws:
             f.write(f"    {w},\n")
         f.write(f"];\n")
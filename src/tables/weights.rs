@@ -1,6 +1,6 @@
 #![allow(clippy::unreadable_literal)]
 
-pub const WEIGHTS: [f64; 46976] = [
+pub static WEIGHTS: [f64; 46976] = [
     0.166666666666667,
     0.166666666666667,
     0.166666666666667,
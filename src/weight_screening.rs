@@ -0,0 +1,71 @@
+#[cfg(feature = "python")]
+use pyo3::prelude::*;
+
+use crate::error::NumgridError;
+
+/// Grid without the points of negligible weight, and what was removed.
+#[cfg_attr(feature = "python", pyclass(get_all, frozen))]
+#[derive(Clone, Debug, PartialEq)]
+pub struct ScreenedGrid {
+    pub coordinates_bohr: Vec<(f64, f64, f64)>,
+    pub weights: Vec<f64>,
+    /// Center index of each remaining point, if center indices were given.
+    pub center_indices: Option<Vec<usize>>,
+    /// Positions of the remaining points in the input grid.
+    pub point_indices: Vec<usize>,
+    pub num_removed: usize,
+    /// Sum of the absolute values of the removed weights. The error of any
+    /// integral is at most this times the largest value of the integrand on
+    /// the removed points.
+    pub discarded_weight: f64,
+}
+
+/// Removes all points whose weight is smaller in magnitude than `threshold`.
+pub fn screen_weights(
+    coordinates_bohr: Vec<(f64, f64, f64)>,
+    weights: Vec<f64>,
+    threshold: f64,
+    center_indices: Option<Vec<usize>>,
+) -> Result<ScreenedGrid, NumgridError> {
+    if weights.len() != coordinates_bohr.len() {
+        return Err(NumgridError::InvalidInput(format!(
+            "{} coordinates but {} weights",
+            coordinates_bohr.len(),
+            weights.len()
+        )));
+    }
+    if let Some(center_indices) = &center_indices {
+        if center_indices.len() != coordinates_bohr.len() {
+            return Err(NumgridError::InvalidInput(format!(
+                "{} coordinates but {} center indices",
+                coordinates_bohr.len(),
+                center_indices.len()
+            )));
+        }
+    }
+    if threshold.is_nan() || threshold < 0.0 {
+        return Err(NumgridError::InvalidInput(
+            "weight threshold has to be zero or positive".to_string(),
+        ));
+    }
+
+    let point_indices: Vec<usize> = (0..weights.len())
+        .filter(|&i| weights[i].abs() >= threshold)
+        .collect();
+
+    let discarded_weight = weights
+        .iter()
+        .filter(|w| w.abs() < threshold)
+        .map(|w| w.abs())
+        .sum();
+
+    Ok(ScreenedGrid {
+        coordinates_bohr: point_indices.iter().map(|&i| coordinates_bohr[i]).collect(),
+        weights: point_indices.iter().map(|&i| weights[i]).collect(),
+        center_indices: center_indices
+            .map(|center_indices| point_indices.iter().map(|&i| center_indices[i]).collect()),
+        num_removed: weights.len() - point_indices.len(),
+        point_indices,
+        discarded_weight,
+    })
+}
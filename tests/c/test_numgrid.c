@@ -0,0 +1,216 @@
+/* Tests the C interface on water, compares against the reference grid in the
+ * file given as first argument. */
+
+#include <math.h>
+#include <stdio.h>
+#include <stdlib.h>
+
+#include "numgrid.h"
+
+static int num_failures = 0;
+
+#define CHECK(condition)                                                       \
+  do {                                                                         \
+    if (!(condition)) {                                                        \
+      char message[256];                                                       \
+      numgrid_last_error_message(message, sizeof(message));                    \
+      fprintf(stderr, "%s:%d: check failed: %s (last error: %s)\n", __FILE__,  \
+              __LINE__, #condition, message);                                  \
+      num_failures++;                                                          \
+    }                                                                          \
+  } while (0)
+
+static int is_close(double a, double b, double rel) {
+  return fabs(a - b) <= rel * fabs(b) + 1.0e-12;
+}
+
+static const size_t num_centers = 3;
+static const int proton_charges[] = {8, 1, 1};
+static const double center_x_bohr[] = {0.0, 1.43, -1.43};
+static const double center_y_bohr[] = {0.0, 0.0, 0.0};
+static const double center_z_bohr[] = {0.0, 1.1, 1.1};
+
+/* cc-pVDZ, smallest exponents for s, p, d */
+static const double alpha_min[] = {0.3023, 0.2753, 1.185, /* O */
+                                   0.122,  0.727,  0.0,   /* H */
+                                   0.122,  0.727,  0.0 /* H */};
+static const size_t num_alpha_min = 3;
+static const double alpha_max[] = {11720.0, 13.01, 13.01};
+
+static void test_angular_grid(void) {
+  double x[14], y[14], z[14], w[14];
+  double sum = 0.0;
+  int i;
+
+  CHECK(numgrid_angular_grid(14, x, y, z, w) == NUMGRID_SUCCESS);
+  for (i = 0; i < 14; i++) {
+    sum += w[i];
+    CHECK(is_close(x[i] * x[i] + y[i] * y[i] + z[i] * z[i], 1.0, 1.0e-12));
+  }
+  CHECK(is_close(sum, 1.0, 1.0e-12));
+
+  CHECK(numgrid_angular_grid(15, x, y, z, w) ==
+        NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID);
+  CHECK(numgrid_last_error_message(NULL, 0) > 0);
+}
+
+static void test_radial_grids(void) {
+  double r[200], w[200];
+  double integral = 0.0;
+  size_t num_points = 0;
+  int i;
+
+  /* integrates exp(-r^2) r^2 */
+  CHECK(numgrid_radial_grid_ta(100, 8, r, w) == NUMGRID_SUCCESS);
+  for (i = 0; i < 100; i++) {
+    integral += w[i] * exp(-r[i] * r[i]);
+  }
+  CHECK(is_close(integral, sqrt(M_PI) / 4.0, 1.0e-12));
+
+  CHECK(numgrid_radial_grid_kk(100, r, w) == NUMGRID_SUCCESS);
+  CHECK(numgrid_radial_grid_mk(100, 8, r, w) == NUMGRID_SUCCESS);
+  CHECK(numgrid_radial_grid_em(100, 8, r, w) == NUMGRID_SUCCESS);
+  CHECK(numgrid_radial_grid_becke(100, 8, r, w) == NUMGRID_SUCCESS);
+  CHECK(numgrid_radial_grid_ta(100, 0, r, w) ==
+        NUMGRID_ERROR_UNSUPPORTED_ELEMENT);
+
+  CHECK(numgrid_radial_grid_lmg(alpha_min, num_alpha_min, alpha_max[0],
+                                1.0e-12, 8, 0, NULL, NULL,
+                                &num_points) == NUMGRID_ERROR_BUFFER_TOO_SMALL);
+  CHECK(num_points > 0 && num_points <= 200);
+  CHECK(numgrid_radial_grid_lmg(alpha_min, num_alpha_min, alpha_max[0],
+                                1.0e-12, 8, 200, r, w,
+                                &num_points) == NUMGRID_SUCCESS);
+}
+
+static void test_water(const char *reference_file_name) {
+  const size_t reference_num_points[] = {16364, 14928, 14928};
+  size_t total_num_points = 0;
+  size_t num_points, center_index, i, offset;
+  double *x, *y, *z, *w, *x_mol, *y_mol, *z_mol, *w_mol;
+  size_t *center_indices;
+  FILE *reference_file;
+
+  for (center_index = 0; center_index < num_centers; center_index++) {
+    CHECK(numgrid_atom_grid_num_points(
+              &alpha_min[center_index * num_alpha_min], num_alpha_min,
+              alpha_max[center_index], 1.0e-12, 86, 302, num_centers,
+              proton_charges, center_x_bohr, center_y_bohr, center_z_bohr,
+              center_index, &num_points) == NUMGRID_SUCCESS);
+    CHECK(num_points == reference_num_points[center_index]);
+    total_num_points += num_points;
+  }
+
+  CHECK(numgrid_molecular_grid_num_points(
+            alpha_min, num_alpha_min, alpha_max, 1.0e-12, 86, 302, num_centers,
+            proton_charges, center_x_bohr, center_y_bohr, center_z_bohr,
+            &num_points) == NUMGRID_SUCCESS);
+  CHECK(num_points == total_num_points);
+
+  x = malloc(total_num_points * sizeof(double));
+  y = malloc(total_num_points * sizeof(double));
+  z = malloc(total_num_points * sizeof(double));
+  w = malloc(total_num_points * sizeof(double));
+
+  offset = 0;
+  for (center_index = 0; center_index < num_centers; center_index++) {
+    CHECK(numgrid_atom_grid(&alpha_min[center_index * num_alpha_min],
+                            num_alpha_min, alpha_max[center_index], 1.0e-12, 86,
+                            302, num_centers, proton_charges, center_x_bohr,
+                            center_y_bohr, center_z_bohr, center_index, 3,
+                            NUMGRID_PARTITIONING_BECKE,
+                            total_num_points - offset, &x[offset], &y[offset],
+                            &z[offset], &w[offset],
+                            &num_points) == NUMGRID_SUCCESS);
+    offset += num_points;
+  }
+  CHECK(offset == total_num_points);
+
+  reference_file = fopen(reference_file_name, "r");
+  CHECK(reference_file != NULL);
+  if (reference_file != NULL) {
+    for (i = 0; i < total_num_points; i++) {
+      double xr, yr, zr, wr;
+      CHECK(fscanf(reference_file, "%lf %lf %lf %lf", &xr, &yr, &zr, &wr) == 4);
+      if (!(is_close(x[i], xr, 1.0e-9) && is_close(y[i], yr, 1.0e-9) &&
+            is_close(z[i], zr, 1.0e-9) && is_close(w[i], wr, 1.0e-9))) {
+        fprintf(stderr, "point %zu differs from reference\n", i);
+        num_failures++;
+        break;
+      }
+    }
+    fclose(reference_file);
+  }
+
+  x_mol = malloc(total_num_points * sizeof(double));
+  y_mol = malloc(total_num_points * sizeof(double));
+  z_mol = malloc(total_num_points * sizeof(double));
+  w_mol = malloc(total_num_points * sizeof(double));
+  center_indices = malloc(total_num_points * sizeof(size_t));
+
+  CHECK(numgrid_molecular_grid(
+            alpha_min, num_alpha_min, alpha_max, 1.0e-12, 86, 302, num_centers,
+            proton_charges, center_x_bohr, center_y_bohr, center_z_bohr, 3,
+            NUMGRID_PARTITIONING_BECKE, total_num_points - 1, x_mol, y_mol,
+            z_mol, w_mol, center_indices,
+            &num_points) == NUMGRID_ERROR_BUFFER_TOO_SMALL);
+  CHECK(num_points == total_num_points);
+
+  CHECK(numgrid_molecular_grid(
+            alpha_min, num_alpha_min, alpha_max, 1.0e-12, 86, 302, num_centers,
+            proton_charges, center_x_bohr, center_y_bohr, center_z_bohr, 3,
+            NUMGRID_PARTITIONING_BECKE, total_num_points, x_mol, y_mol, z_mol,
+            w_mol, center_indices, &num_points) == NUMGRID_SUCCESS);
+  CHECK(num_points == total_num_points);
+  for (i = 0; i < total_num_points; i++) {
+    if (x_mol[i] != x[i] || y_mol[i] != y[i] || z_mol[i] != z[i] ||
+        w_mol[i] != w[i]) {
+      fprintf(stderr, "molecular grid differs from atom grids at %zu\n", i);
+      num_failures++;
+      break;
+    }
+  }
+  CHECK(center_indices[0] == 0);
+  CHECK(center_indices[total_num_points - 1] == 2);
+
+  /* center index out of range */
+  CHECK(numgrid_atom_grid(alpha_min, num_alpha_min, alpha_max[0], 1.0e-12, 86,
+                          302, num_centers, proton_charges, center_x_bohr,
+                          center_y_bohr, center_z_bohr, 3, 3,
+                          NUMGRID_PARTITIONING_BECKE, total_num_points, x, y, z,
+                          w, &num_points) == NUMGRID_ERROR_INVALID_INPUT);
+
+  /* unknown partitioning */
+  CHECK(numgrid_atom_grid(alpha_min, num_alpha_min, alpha_max[0], 1.0e-12, 86,
+                          302, num_centers, proton_charges, center_x_bohr,
+                          center_y_bohr, center_z_bohr, 0, 3, 7,
+                          total_num_points, x, y, z, w,
+                          &num_points) == NUMGRID_ERROR_INVALID_INPUT);
+
+  free(x);
+  free(y);
+  free(z);
+  free(w);
+  free(x_mol);
+  free(y_mol);
+  free(z_mol);
+  free(w_mol);
+  free(center_indices);
+}
+
+int main(int argc, char **argv) {
+  if (argc != 2) {
+    fprintf(stderr, "usage: %s reference-grid-file\n", argv[0]);
+    return EXIT_FAILURE;
+  }
+
+  test_angular_grid();
+  test_radial_grids();
+  test_water(argv[1]);
+
+  if (num_failures > 0) {
+    fprintf(stderr, "%d checks failed\n", num_failures);
+    return EXIT_FAILURE;
+  }
+  return EXIT_SUCCESS;
+}
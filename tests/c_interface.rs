@@ -0,0 +1,100 @@
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+// target/<profile>/deps/<test binary>; cargo test builds the cdylib next to it
+// with the features of this run, whereas the copy in target/<profile> is only
+// refreshed by cargo build and may be stale
+fn library_dir() -> PathBuf {
+    let library_dir = env::current_exe().unwrap().parent().unwrap().to_path_buf();
+    assert!(
+        library_dir
+            .join(format!(
+                "{}numgrid{}",
+                env::consts::DLL_PREFIX,
+                env::consts::DLL_SUFFIX
+            ))
+            .exists(),
+        "cdylib not found in {}",
+        library_dir.display()
+    );
+    library_dir
+}
+
+// with the Python bindings compiled in, the library refers to the Python C API
+// which is otherwise provided by the interpreter
+fn python_ldflags() -> String {
+    if cfg!(feature = "python") {
+        let output = Command::new("python3-config")
+            .args(["--ldflags", "--embed"])
+            .output()
+            .expect("python3-config is needed to link the C and Fortran tests");
+        String::from_utf8(output.stdout).unwrap()
+    } else {
+        String::new()
+    }
+}
+
+// builds tests/c/test_numgrid.c against the cdylib and runs it
+#[test]
+fn c_interface() {
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let executable = library_dir.join("test_numgrid_c");
+
+    let status = Command::new(env::var("CC").unwrap_or_else(|_| "cc".to_string()))
+        .arg(root.join("tests/c/test_numgrid.c"))
+        .arg(format!("-I{}", root.join("include").display()))
+        .arg(format!("-L{}", library_dir.display()))
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-lnumgrid")
+        .args(python_ldflags().split_whitespace())
+        .arg("-lm")
+        .arg("-o")
+        .arg(&executable)
+        .status()
+        .expect("C compiler is needed for the C test");
+    assert!(status.success());
+
+    let status = Command::new(&executable)
+        .arg(root.join("tests/reference/cc-pVDZ.txt"))
+        .status()
+        .unwrap();
+    assert!(status.success());
+}
+
+// builds fortran/numgrid.f90 and tests/fortran/test_numgrid.f90 against the
+// cdylib and runs it; skipped if gfortran is not installed
+#[test]
+fn fortran_interface() {
+    if Command::new("gfortran").arg("--version").output().is_err() {
+        eprintln!("gfortran not found, skipping the Fortran test");
+        return;
+    }
+
+    let root = PathBuf::from(env!("CARGO_MANIFEST_DIR"));
+    let library_dir = library_dir();
+    let executable = library_dir.join("test_numgrid_fortran");
+
+    // the numgrid module file is written here instead of the working directory
+    let module_dir = library_dir.join("test_numgrid_fortran_modules");
+    fs::create_dir_all(&module_dir).unwrap();
+
+    let status = Command::new("gfortran")
+        .arg(root.join("fortran/numgrid.f90"))
+        .arg(root.join("tests/fortran/test_numgrid.f90"))
+        .arg(format!("-J{}", module_dir.display()))
+        .arg(format!("-L{}", library_dir.display()))
+        .arg(format!("-Wl,-rpath,{}", library_dir.display()))
+        .arg("-lnumgrid")
+        .args(python_ldflags().split_whitespace())
+        .arg("-o")
+        .arg(&executable)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    let status = Command::new(&executable).status().unwrap();
+    assert!(status.success());
+}
@@ -0,0 +1,159 @@
+! Tests the Fortran interface on water: the molecular grid has to agree with
+! the atom grids, and errors have to be reported.
+
+program test_numgrid
+
+   use, intrinsic :: iso_c_binding, only: c_int, c_size_t, c_double
+   use numgrid
+
+   implicit none
+
+   integer(c_size_t), parameter :: num_centers = 3
+   integer(c_int) :: proton_charges(3) = [8, 1, 1]
+   real(c_double) :: center_x_bohr(3) = [0.0d0, 1.43d0, -1.43d0]
+   real(c_double) :: center_y_bohr(3) = [0.0d0, 0.0d0, 0.0d0]
+   real(c_double) :: center_z_bohr(3) = [0.0d0, 1.1d0, 1.1d0]
+
+   ! cc-pVDZ, smallest exponents for s, p, d
+   integer(c_size_t), parameter :: num_alpha_min = 3
+   real(c_double) :: alpha_min(9) = [0.3023d0, 0.2753d0, 1.185d0, &
+                                     0.122d0, 0.727d0, 0.0d0, &
+                                     0.122d0, 0.727d0, 0.0d0]
+   real(c_double) :: alpha_max(3) = [11720.0d0, 13.01d0, 13.01d0]
+
+   integer :: num_failures = 0
+
+   call test_angular_grid()
+   call test_water()
+
+   if (num_failures > 0) then
+      error stop 1
+   end if
+
+contains
+
+   subroutine check(condition, description)
+      logical, intent(in) :: condition
+      character(len=*), intent(in) :: description
+
+      if (.not. condition) then
+         write (*, '(4a)') 'check failed: ', description, &
+            ' (last error: ', numgrid_last_error_message()//')'
+         num_failures = num_failures + 1
+      end if
+   end subroutine
+
+   subroutine test_angular_grid()
+      real(c_double) :: x(14), y(14), z(14), w(14)
+
+      call check(numgrid_angular_grid(14_c_size_t, x, y, z, w) == NUMGRID_SUCCESS, &
+                 'angular grid')
+      call check(abs(sum(w) - 1.0d0) < 1.0d-12, 'sum of angular weights')
+      call check(all(abs(x**2 + y**2 + z**2 - 1.0d0) < 1.0d-12), &
+                 'angular points on the unit sphere')
+
+      call check(numgrid_angular_grid(15_c_size_t, x, y, z, w) == &
+                 NUMGRID_ERROR_UNSUPPORTED_ANGULAR_GRID, 'unsupported angular grid')
+      call check(len(numgrid_last_error_message()) > 0, 'error message')
+   end subroutine
+
+   subroutine test_water()
+      integer(c_size_t), parameter :: reference_num_points(3) = &
+         [16364_c_size_t, 14928_c_size_t, 14928_c_size_t]
+      real(c_double), allocatable :: x(:), y(:), z(:), w(:)
+      real(c_double), allocatable :: x_mol(:), y_mol(:), z_mol(:), w_mol(:)
+      integer(c_size_t), allocatable :: center_indices(:)
+      integer(c_size_t) :: center_index, num_points, total_num_points, offset
+
+      total_num_points = 0
+      do center_index = 0, num_centers - 1
+         call check(numgrid_atom_grid_num_points( &
+                    alpha_min(1 + center_index*num_alpha_min), num_alpha_min, &
+                    alpha_max(1 + center_index), 1.0d-12, 86_c_size_t, 302_c_size_t, &
+                    num_centers, proton_charges, center_x_bohr, center_y_bohr, &
+                    center_z_bohr, center_index, num_points) == NUMGRID_SUCCESS, &
+                    'number of atom grid points')
+         call check(num_points == reference_num_points(1 + center_index), &
+                    'number of atom grid points of the reference')
+         total_num_points = total_num_points + num_points
+      end do
+
+      call check(numgrid_molecular_grid_num_points( &
+                 alpha_min, num_alpha_min, alpha_max, 1.0d-12, 86_c_size_t, &
+                 302_c_size_t, num_centers, proton_charges, center_x_bohr, &
+                 center_y_bohr, center_z_bohr, num_points) == NUMGRID_SUCCESS, &
+                 'number of molecular grid points')
+      call check(num_points == total_num_points, 'sum of the atom grid points')
+
+      allocate (x(total_num_points), y(total_num_points), z(total_num_points), &
+                w(total_num_points))
+
+      offset = 0
+      do center_index = 0, num_centers - 1
+         call check(numgrid_atom_grid( &
+                    alpha_min(1 + center_index*num_alpha_min), num_alpha_min, &
+                    alpha_max(1 + center_index), 1.0d-12, 86_c_size_t, 302_c_size_t, &
+                    num_centers, proton_charges, center_x_bohr, center_y_bohr, &
+                    center_z_bohr, center_index, 3_c_size_t, &
+                    NUMGRID_PARTITIONING_BECKE, total_num_points - offset, &
+                    x(1 + offset), y(1 + offset), z(1 + offset), w(1 + offset), &
+                    num_points) == NUMGRID_SUCCESS, 'atom grid')
+         offset = offset + num_points
+      end do
+      call check(offset == total_num_points, 'atom grids fill the buffers')
+
+      ! integrates the number of electrons of a model density
+      call check(abs(sum(w*water_density(x, y, z)) - 10.0d0) < 1.0d-5, &
+                 'integral over the atom grids')
+
+      allocate (x_mol(total_num_points), y_mol(total_num_points), &
+                z_mol(total_num_points), w_mol(total_num_points), &
+                center_indices(total_num_points))
+
+      call check(numgrid_molecular_grid( &
+                 alpha_min, num_alpha_min, alpha_max, 1.0d-12, 86_c_size_t, &
+                 302_c_size_t, num_centers, proton_charges, center_x_bohr, &
+                 center_y_bohr, center_z_bohr, 3_c_size_t, NUMGRID_PARTITIONING_BECKE, &
+                 total_num_points - 1, x_mol, y_mol, z_mol, w_mol, center_indices, &
+                 num_points) == NUMGRID_ERROR_BUFFER_TOO_SMALL, 'buffer too small')
+      call check(num_points == total_num_points, 'required buffer size')
+
+      call check(numgrid_molecular_grid( &
+                 alpha_min, num_alpha_min, alpha_max, 1.0d-12, 86_c_size_t, &
+                 302_c_size_t, num_centers, proton_charges, center_x_bohr, &
+                 center_y_bohr, center_z_bohr, 3_c_size_t, NUMGRID_PARTITIONING_BECKE, &
+                 total_num_points, x_mol, y_mol, z_mol, w_mol, center_indices, &
+                 num_points) == NUMGRID_SUCCESS, 'molecular grid')
+      call check(num_points == total_num_points, 'number of molecular grid points')
+      call check(all(x_mol == x) .and. all(y_mol == y) .and. all(z_mol == z) &
+                 .and. all(w_mol == w), 'molecular grid equals the atom grids')
+      call check(center_indices(1) == 0, 'first center index')
+      call check(center_indices(total_num_points) == 2, 'last center index')
+
+      ! center index out of range
+      call check(numgrid_atom_grid( &
+                 alpha_min, num_alpha_min, alpha_max(1), 1.0d-12, 86_c_size_t, &
+                 302_c_size_t, num_centers, proton_charges, center_x_bohr, &
+                 center_y_bohr, center_z_bohr, 3_c_size_t, 3_c_size_t, &
+                 NUMGRID_PARTITIONING_BECKE, total_num_points, x, y, z, w, &
+                 num_points) == NUMGRID_ERROR_INVALID_INPUT, 'center index out of range')
+   end subroutine
+
+   ! normalized Gaussians at the nuclei, with 8 electrons at O and 1 at each H
+   elemental function water_density(x, y, z) result(density)
+      real(c_double), intent(in) :: x, y, z
+      real(c_double) :: density
+      real(c_double), parameter :: pi = 3.14159265358979323846d0
+      real(c_double), parameter :: electrons(3) = [8.0d0, 1.0d0, 1.0d0]
+      real(c_double), parameter :: xs(3) = [0.0d0, 1.43d0, -1.43d0]
+      real(c_double), parameter :: zs(3) = [0.0d0, 1.1d0, 1.1d0]
+      integer :: i
+
+      density = 0.0d0
+      do i = 1, 3
+         density = density + electrons(i)*(1.0d0/pi)**1.5d0 &
+                   *exp(-((x - xs(i))**2 + y**2 + (z - zs(i))**2))
+      end do
+   end function
+
+end program
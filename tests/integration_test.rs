@@ -10,15 +10,15 @@ fn floats_are_same(value: f64, reference: f64, threshold: f64) -> bool {
     let absolute_error = (value - reference).abs();
     if reference.abs() > threshold {
         let relative_error = (absolute_error / reference).abs();
-        return relative_error < threshold;
+        relative_error < threshold
     } else {
-        return absolute_error < threshold;
+        absolute_error < threshold
     }
 }
 
 #[test]
 fn angular_grid() {
-    let (coordinates, weights) = numgrid::angular_grid(50);
+    let (coordinates, weights) = numgrid::angular_grid(50).unwrap();
 
     let coordinates_reference: [(f64, f64, f64); 50] = [
         (1.0, 0.0, 0.0),
@@ -165,6 +165,88 @@ fn angular_grid() {
     }
 }
 
+#[test]
+fn invalid_input() {
+    assert!(matches!(
+        numgrid::angular_grid(15),
+        Err(numgrid::NumgridError::UnsupportedAngularGrid { .. })
+    ));
+
+    let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+    alpha_min.insert(0, 0.3023);
+
+    // unsupported angular grid
+    assert!(numgrid::atom_grid(
+        alpha_min.clone(),
+        11720.0,
+        1.0e-12,
+        15,
+        302,
+        vec![8],
+        0,
+        vec![(0.0, 0.0, 0.0)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .is_err());
+
+    // center_index out of range
+    assert!(numgrid::atom_grid(
+        alpha_min.clone(),
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        vec![8],
+        1,
+        vec![(0.0, 0.0, 0.0)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .is_err());
+
+    // inconsistent number of charges and centers
+    assert!(numgrid::atom_grid(
+        alpha_min.clone(),
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        vec![8, 1],
+        0,
+        vec![(0.0, 0.0, 0.0)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .is_err());
+
+    // unsupported element on a neighboring center
+    assert!(matches!(
+        numgrid::atom_grid(
+            alpha_min,
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            vec![8, 0],
+            0,
+            vec![(0.0, 0.0, 0.0), (1.0, 0.0, 0.0)],
+            3,
+            numgrid::Partitioning::Becke,
+        ),
+        Err(numgrid::NumgridError::UnsupportedElement(0))
+    ));
+
+    assert!(matches!(
+        numgrid::alpha_min_and_max("tests/basis/cc-pvdz.gbs", 6),
+        Err(numgrid::NumgridError::NotFound(_))
+    ));
+    assert!(matches!(
+        numgrid::alpha_min_and_max("tests/basis/does-not-exist.gbs", 8),
+        Err(numgrid::NumgridError::Io(_))
+    ));
+}
+
 #[test]
 fn radial_grid_lmg() {
     let mut alpha_min: HashMap<usize, f64> = HashMap::new();
@@ -172,7 +254,7 @@ fn radial_grid_lmg() {
     alpha_min.insert(1, 0.2753);
     alpha_min.insert(2, 1.185);
 
-    let (rs, ws) = numgrid::radial_grid_lmg(alpha_min, 11720.0, 1.0e-12, 8);
+    let (rs, ws) = numgrid::radial_grid_lmg(alpha_min, 11720.0, 1.0e-12, 8).unwrap();
 
     let rs_reference: [f64; 106] = [
         0.0000012304794589759454,
@@ -429,9 +511,7 @@ where
 {
     let error_message = format!("something went wrong reading file {}", file_name);
     let contents = fs::read_to_string(file_name).expect(&error_message);
-    let v = contents.lines().map(|s| s.parse().unwrap()).collect();
-
-    return v;
+    contents.lines().map(|s| s.parse().unwrap()).collect()
 }
 
 #[test]
@@ -461,7 +541,9 @@ fn atom_grid() {
         center_index,
         center_coordinates_bohr,
         hardness,
-    );
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
 
     let num_points = rs.len();
     assert_eq!(num_points, 5300);
@@ -475,6 +557,65 @@ fn atom_grid() {
     }
 }
 
+#[test]
+fn atom_grid_bse_from_file() {
+    let (rs, ws) = numgrid::atom_grid_bse(
+        "tests/basis/cc-pvdz.1.json",
+        1.0e-12,
+        50,
+        50,
+        vec![8],
+        0,
+        vec![(0.0, 0.0, 0.0)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+
+    assert_eq!(rs.len(), 5300);
+
+    let reference_points: Vec<GridPoint> = read_vector("tests/reference/atom.txt");
+    for (i, p) in reference_points.iter().enumerate() {
+        assert!(floats_are_same(p.x, rs[i].0, 1.0e-15));
+        assert!(floats_are_same(p.y, rs[i].1, 1.0e-15));
+        assert!(floats_are_same(p.z, rs[i].2, 1.0e-15));
+        assert!(floats_are_same(p.w, ws[i], 1.0e-15));
+    }
+
+    let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+    alpha_min.insert(0, 0.122);
+    alpha_min.insert(1, 0.727);
+
+    let (rs, ws) = numgrid::radial_grid_lmg_bse("tests/basis/cc-pvdz.1.json", 1.0e-12, 1).unwrap();
+    let (rs_reference, ws_reference) =
+        numgrid::radial_grid_lmg(alpha_min, 13.01, 1.0e-12, 1).unwrap();
+    assert_eq!(rs, rs_reference);
+    assert_eq!(ws, ws_reference);
+}
+
+#[test]
+fn alpha_min_and_max() {
+    for file_name in [
+        "tests/basis/cc-pvdz.1.json",
+        "tests/basis/cc-pvdz.gbs",
+        "tests/basis/cc-pvdz.nw",
+        "tests/basis/basis",
+    ] {
+        let (alpha_min, alpha_max) = numgrid::alpha_min_and_max(file_name, 8).unwrap();
+        assert_eq!(alpha_max, 11720.0);
+        assert_eq!(alpha_min.len(), 3);
+        assert_eq!(alpha_min[&0], 0.3023);
+        assert_eq!(alpha_min[&1], 0.2753);
+        assert_eq!(alpha_min[&2], 1.185);
+
+        let (alpha_min, alpha_max) = numgrid::alpha_min_and_max(file_name, 1).unwrap();
+        assert_eq!(alpha_max, 13.01);
+        assert_eq!(alpha_min.len(), 2);
+        assert_eq!(alpha_min[&0], 0.122);
+        assert_eq!(alpha_min[&1], 0.727);
+    }
+}
+
 #[test]
 fn molecular_grid() {
     let radial_precision = 1.0e-12;
@@ -488,9 +629,9 @@ fn molecular_grid() {
     alpha_min_h.insert(0, 0.122);
     alpha_min_h.insert(1, 0.727);
 
-    let alpha_min = vec![alpha_min_o, alpha_min_h.clone(), alpha_min_h];
+    let alpha_min = [alpha_min_o, alpha_min_h.clone(), alpha_min_h];
 
-    let alpha_max = vec![11720.0, 13.01, 13.01];
+    let alpha_max = [11720.0, 13.01, 13.01];
     let min_num_angular_points = 50;
     let max_num_angular_points = 50;
     let num_centers = 3;
@@ -512,7 +653,9 @@ fn molecular_grid() {
             center_index,
             center_coordinates_bohr.clone(),
             hardness,
-        );
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
         num_points += rs_atom.len();
         rs.extend(rs_atom);
         ws.extend(ws_atom);
@@ -530,6 +673,1202 @@ fn molecular_grid() {
     }
 }
 
+#[test]
+fn molecular_grid_in_one_call() {
+    let radial_precision = 1.0e-12;
+
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let alpha_min = vec![alpha_min_o, alpha_min_h.clone(), alpha_min_h];
+
+    let alpha_max = vec![11720.0, 13.01, 13.01];
+    let min_num_angular_points = 50;
+    let max_num_angular_points = 50;
+    let proton_charges = vec![8, 1, 1];
+    let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    let hardness = 3;
+
+    let (rs, ws, center_indices) = numgrid::molecular_grid(
+        alpha_min,
+        alpha_max,
+        radial_precision,
+        min_num_angular_points,
+        max_num_angular_points,
+        proton_charges,
+        center_coordinates_bohr,
+        hardness,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+
+    assert_eq!(rs.len(), 5300 + 3900 + 3900);
+    assert_eq!(ws.len(), rs.len());
+    assert_eq!(center_indices.len(), rs.len());
+
+    assert!(center_indices[..5300].iter().all(|&i| i == 0));
+    assert!(center_indices[5300..9200].iter().all(|&i| i == 1));
+    assert!(center_indices[9200..].iter().all(|&i| i == 2));
+
+    let reference_points: Vec<GridPoint> = read_vector("tests/reference/molecule.txt");
+    for (i, p) in reference_points.iter().enumerate() {
+        assert!(floats_are_same(p.x, rs[i].0, 1.0e-15));
+        assert!(floats_are_same(p.y, rs[i].1, 1.0e-15));
+        assert!(floats_are_same(p.z, rs[i].2, 1.0e-15));
+        assert!(floats_are_same(p.w, ws[i], 1.0e-15));
+    }
+}
+
+fn water_grid(partitioning: numgrid::Partitioning) -> (Vec<(f64, f64, f64)>, Vec<f64>) {
+    let radial_precision = 1.0e-12;
+
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let alpha_min = [alpha_min_o, alpha_min_h.clone(), alpha_min_h];
+
+    let alpha_max = [11720.0, 13.01, 13.01];
+    let min_num_angular_points = 86;
+    let max_num_angular_points = 302;
+    let proton_charges = vec![8, 1, 1];
+    let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    let hardness = 3;
+
+    let mut rs = Vec::new();
+    let mut ws = Vec::new();
+    for center_index in 0..center_coordinates_bohr.len() {
+        let (rs_atom, ws_atom) = numgrid::atom_grid(
+            alpha_min[center_index].clone(),
+            alpha_max[center_index],
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            proton_charges.clone(),
+            center_index,
+            center_coordinates_bohr.clone(),
+            hardness,
+            partitioning,
+        )
+        .unwrap();
+        rs.extend(rs_atom);
+        ws.extend(ws_atom);
+    }
+
+    (rs, ws)
+}
+
+// normalized Gaussians on each atom and on the O-H bond midpoints
+fn water_test_density(p: &(f64, f64, f64)) -> f64 {
+    let centers = [
+        ((0.0, 0.0, 0.0), 2.0),
+        ((1.43, 0.0, 1.1), 0.8),
+        ((-1.43, 0.0, 1.1), 0.8),
+        ((0.715, 0.0, 0.55), 1.2),
+        ((-0.715, 0.0, 0.55), 1.2),
+    ];
+
+    centers
+        .iter()
+        .map(|&(c, alpha): &((f64, f64, f64), f64)| {
+            let dx = p.0 - c.0;
+            let dy = p.1 - c.1;
+            let dz = p.2 - c.2;
+            let norm = (alpha / std::f64::consts::PI).powf(1.5);
+            norm * (-alpha * (dx * dx + dy * dy + dz * dz)).exp()
+        })
+        .sum()
+}
+
+#[test]
+fn ssf_partitioning() {
+    let (rs_becke, ws_becke) = water_grid(numgrid::Partitioning::Becke);
+    let (rs_ssf, ws_ssf) = water_grid(numgrid::Partitioning::Ssf);
+
+    // partitioning only changes the weights, not the points
+    assert_eq!(rs_becke.len(), rs_ssf.len());
+
+    let integral = |rs: &[(f64, f64, f64)], ws: &[f64]| -> f64 {
+        rs.iter()
+            .zip(ws.iter())
+            .map(|(p, w)| w * water_test_density(p))
+            .sum()
+    };
+
+    let integral_becke = integral(&rs_becke, &ws_becke);
+    let integral_ssf = integral(&rs_ssf, &ws_ssf);
+
+    assert!(floats_are_same(integral_becke, 5.0, 1.0e-6));
+    assert!(floats_are_same(integral_ssf, 5.0, 1.0e-6));
+    assert!(floats_are_same(integral_ssf, integral_becke, 1.0e-6));
+}
+
+#[test]
+fn atom_grid_from_radial() {
+    let proton_charges = vec![8, 1, 1];
+    let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+
+    type RadialGrid = fn(usize, i32) -> Result<(Vec<f64>, Vec<f64>), numgrid::NumgridError>;
+    let radial_grids: [RadialGrid; 4] = [
+        numgrid::radial_grid_em,
+        numgrid::radial_grid_becke,
+        numgrid::radial_grid_mk,
+        numgrid::radial_grid_ta,
+    ];
+
+    for radial_grid in radial_grids {
+        let mut integral = 0.0;
+        for center_index in 0..center_coordinates_bohr.len() {
+            let (radii, radial_weights) = radial_grid(100, proton_charges[center_index]).unwrap();
+            let (rs, ws) = numgrid::atom_grid_from_radial(
+                radii,
+                radial_weights,
+                86,
+                302,
+                proton_charges.clone(),
+                center_index,
+                center_coordinates_bohr.clone(),
+                3,
+                numgrid::Partitioning::Becke,
+            )
+            .unwrap();
+            integral += rs
+                .iter()
+                .zip(ws.iter())
+                .map(|(p, w)| w * water_test_density(p))
+                .sum::<f64>();
+        }
+        assert!(floats_are_same(integral, 5.0, 1.0e-5));
+    }
+
+    // the LMG grid passed explicitly gives the same as atom_grid
+    let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+    alpha_min.insert(0, 0.122);
+    alpha_min.insert(1, 0.727);
+    let (radii, radial_weights) =
+        numgrid::radial_grid_lmg(alpha_min.clone(), 13.01, 1.0e-12, 1).unwrap();
+    let (rs, ws) = numgrid::atom_grid_from_radial(
+        radii,
+        radial_weights,
+        86,
+        302,
+        proton_charges.clone(),
+        1,
+        center_coordinates_bohr.clone(),
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+    let (rs_ref, ws_ref) = numgrid::atom_grid(
+        alpha_min,
+        13.01,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        1,
+        center_coordinates_bohr,
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+    assert_eq!(rs, rs_ref);
+    assert_eq!(ws, ws_ref);
+}
+
+#[test]
+fn atom_grid_builder() {
+    let proton_charges = [8, 1, 1];
+    let center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+
+    let schemes = [
+        numgrid::RadialScheme::Kk { num_points: 100 },
+        numgrid::RadialScheme::Ta { num_points: 100 },
+        numgrid::RadialScheme::Mk { num_points: 100 },
+        numgrid::RadialScheme::Em { num_points: 100 },
+        numgrid::RadialScheme::Becke { num_points: 100 },
+    ];
+
+    for scheme in schemes {
+        let builder = numgrid::AtomGridBuilder::new(scheme)
+            .angular_points(86, 302)
+            .hardness(3)
+            .partitioning(numgrid::Partitioning::Ssf);
+        let integral: f64 = (0..center_coordinates_bohr.len())
+            .map(|center_index| {
+                let (rs, ws) = builder
+                    .build(&proton_charges, center_index, &center_coordinates_bohr)
+                    .unwrap();
+                rs.iter()
+                    .zip(ws.iter())
+                    .map(|(p, w)| w * water_test_density(p))
+                    .sum::<f64>()
+            })
+            .sum();
+        assert!(floats_are_same(integral, 5.0, 1.0e-5));
+    }
+
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Explicit {
+        radii: vec![1.0, 2.0],
+        weights: vec![1.0],
+    });
+    assert!(builder
+        .build(&proton_charges, 0, &center_coordinates_bohr)
+        .is_err());
+
+    // weight gradients are only implemented for Becke partitioning
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 50 })
+        .partitioning(numgrid::Partitioning::Ssf);
+    assert!(builder
+        .build_with_gradient(&proton_charges, 0, &center_coordinates_bohr)
+        .is_err());
+}
+
+#[test]
+fn standard_grid_sg1() {
+    // number of points of a free atom, one value per element row
+    for (proton_charge, num_points) in [(1, 3720), (6, 3816), (8, 3816), (17, 3760)] {
+        let (rs, ws) = numgrid::standard_atom_grid(
+            numgrid::StandardGrid::Sg1,
+            vec![proton_charge],
+            0,
+            vec![(0.0, 0.0, 0.0)],
+            3,
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
+        assert_eq!(rs.len(), num_points);
+        assert_eq!(ws.len(), num_points);
+    }
+
+    let (rs, ws, center_indices) = numgrid::standard_molecular_grid(
+        numgrid::StandardGrid::Sg1,
+        vec![8, 1, 1],
+        vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+    assert_eq!(rs.len(), 3816 + 2 * 3720);
+    assert_eq!(center_indices.len(), rs.len());
+
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(p, w)| w * water_test_density(p))
+        .sum();
+    assert!(floats_are_same(integral, 5.0, 1.0e-4));
+
+    // the same grid from the builder, in structure-of-arrays layout
+    let (grid, center_indices_soa) =
+        numgrid::MolecularGridBuilder::standard(numgrid::StandardGrid::Sg1, 3)
+            .build_soa(
+                &[8, 1, 1],
+                &[(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)],
+            )
+            .unwrap();
+    assert_eq!(grid, numgrid::GridBatch::from((rs, ws)));
+    assert_eq!(center_indices_soa, center_indices);
+
+    // SG-1 is only defined up to argon
+    assert!(numgrid::standard_atom_grid(
+        numgrid::StandardGrid::Sg1,
+        vec![19],
+        0,
+        vec![(0.0, 0.0, 0.0)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .is_err());
+}
+
+#[test]
+fn custom_pruning() {
+    let proton_charges = vec![8, 1, 1];
+    let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+
+    let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+    alpha_min.insert(0, 0.3023);
+    alpha_min.insert(1, 0.2753);
+    alpha_min.insert(2, 1.185);
+
+    let grid = |pruning: numgrid::Pruning| {
+        numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Lmg {
+            alpha_min: alpha_min.clone(),
+            alpha_max: 11720.0,
+            radial_precision: 1.0e-12,
+        })
+        .angular_points(86, 302)
+        .hardness(3)
+        .pruning(pruning)
+        .build(&proton_charges, 0, &center_coordinates_bohr)
+    };
+
+    // a closure which never prunes
+    let (rs, _) = grid(numgrid::Pruning::Custom(std::sync::Arc::new(|_, _| {
+        Ok(302)
+    })))
+    .unwrap();
+    let (rs_bragg, _) = grid(numgrid::Pruning::Bragg).unwrap();
+    assert_eq!(rs.len() % 302, 0);
+    assert!(rs.len() > rs_bragg.len());
+
+    // a single interval without pruning gives the same grid
+    let (rs_intervals, ws_intervals) = grid(numgrid::Pruning::Intervals {
+        boundaries: vec![],
+        num_points: vec![302],
+    })
+    .unwrap();
+    let (rs_custom, ws_custom) = grid(numgrid::Pruning::Custom(std::sync::Arc::new(|_, _| {
+        Ok(302)
+    })))
+    .unwrap();
+    assert_eq!(rs_intervals, rs_custom);
+    assert_eq!(ws_intervals, ws_custom);
+
+    // pruned grid still integrates well
+    let (rs, ws) = grid(numgrid::Pruning::Intervals {
+        boundaries: vec![0.25, 0.5, 1.0],
+        num_points: vec![14, 50, 110, 302],
+    })
+    .unwrap();
+    assert!(rs.len() < rs_custom.len());
+    let integral: f64 = rs
+        .iter()
+        .zip(ws.iter())
+        .map(|(p, w)| {
+            let r2 = p.0 * p.0 + p.1 * p.1 + p.2 * p.2;
+            w * (2.0 / std::f64::consts::PI).powf(1.5) * (-2.0 * r2).exp()
+        })
+        .sum();
+    let integral_custom: f64 = rs_custom
+        .iter()
+        .zip(ws_custom.iter())
+        .map(|(p, w)| {
+            let r2 = p.0 * p.0 + p.1 * p.1 + p.2 * p.2;
+            w * (2.0 / std::f64::consts::PI).powf(1.5) * (-2.0 * r2).exp()
+        })
+        .sum();
+    assert!(floats_are_same(integral, integral_custom, 1.0e-8));
+
+    // unsupported number of angular points
+    assert!(grid(numgrid::Pruning::Custom(std::sync::Arc::new(|_, _| Ok(
+        303
+    ))))
+    .is_err());
+}
+
+#[test]
+fn molecular_grid_pruning() {
+    let proton_charges = [8, 1, 1];
+    let center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    let pruning = numgrid::Pruning::Intervals {
+        boundaries: vec![0.25, 0.5, 1.0],
+        num_points: vec![14, 50, 110, 302],
+    };
+
+    let (rs, ws, center_indices) =
+        numgrid::MolecularGridBuilder::new(vec![numgrid::RadialScheme::Kk { num_points: 50 }; 3])
+            .pruning(pruning.clone())
+            .build(&proton_charges, &center_coordinates_bohr)
+            .unwrap();
+
+    // same points and weights as the atom grids with the same pruning
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 50 })
+        .pruning(pruning);
+    let mut offset = 0;
+    for center_index in 0..proton_charges.len() {
+        let (rs_atom, ws_atom) = builder
+            .build(&proton_charges, center_index, &center_coordinates_bohr)
+            .unwrap();
+        let n = ws_atom.len();
+        assert_eq!(rs[offset..offset + n], rs_atom[..]);
+        assert_eq!(ws[offset..offset + n], ws_atom[..]);
+        assert!(center_indices[offset..offset + n]
+            .iter()
+            .all(|&i| i == center_index));
+        offset += n;
+    }
+    assert_eq!(offset, ws.len());
+
+    // and fewer points than with the default pruning
+    let (_, ws_bragg, _) =
+        numgrid::MolecularGridBuilder::new(vec![numgrid::RadialScheme::Kk { num_points: 50 }; 3])
+            .build(&proton_charges, &center_coordinates_bohr)
+            .unwrap();
+    assert_ne!(ws.len(), ws_bragg.len());
+}
+
+#[test]
+fn quality_presets() {
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    // relative integration errors documented in the README
+    let qualities = [
+        (numgrid::Quality::Coarse, 1.0e-4),
+        (numgrid::Quality::Medium, 1.0e-5),
+        (numgrid::Quality::Fine, 1.0e-7),
+        (numgrid::Quality::UltraFine, 1.0e-8),
+    ];
+
+    let mut num_points = 0;
+    for (quality, error) in qualities {
+        let (rs, ws, _) = numgrid::molecular_grid_quality(
+            quality,
+            vec![
+                alpha_min_o.clone(),
+                alpha_min_h.clone(),
+                alpha_min_h.clone(),
+            ],
+            vec![11720.0, 13.01, 13.01],
+            vec![8, 1, 1],
+            vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)],
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
+        let integral: f64 = rs
+            .iter()
+            .zip(ws.iter())
+            .map(|(p, w)| w * water_test_density(p))
+            .sum();
+        assert!(floats_are_same(integral, 5.0, error));
+
+        // each level is larger than the previous one
+        assert!(rs.len() > num_points);
+        num_points = rs.len();
+    }
+
+    let p = numgrid::Quality::Fine.parameters(8);
+    assert_eq!(p.radial_precision, 1.0e-12);
+    assert_eq!(p.min_num_angular_points, 86);
+    assert_eq!(p.max_num_angular_points, 302);
+    assert_eq!(p.hardness, 3);
+
+    // heavier elements get more angular points
+    assert!(
+        numgrid::Quality::Fine.parameters(26).max_num_angular_points > p.max_num_angular_points
+    );
+}
+
+#[test]
+fn actinides() {
+    // uranium and americium with a hydrogen neighbor
+    for proton_charge in [92, 95] {
+        let proton_charges = vec![proton_charge, 1];
+        let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (0.0, 0.0, 4.0)];
+
+        let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+        alpha_min.insert(0, 0.02);
+        alpha_min.insert(1, 0.03);
+        alpha_min.insert(2, 0.05);
+        alpha_min.insert(3, 0.2);
+
+        let (rs, ws) = numgrid::atom_grid(
+            alpha_min,
+            5.0e7,
+            1.0e-12,
+            86,
+            302,
+            proton_charges.clone(),
+            0,
+            center_coordinates_bohr.clone(),
+            3,
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
+        assert_eq!(rs.len(), ws.len());
+        assert!(ws.iter().all(|w| w.is_finite()));
+
+        let (rs, _) = numgrid::atom_grid_from_radial(
+            vec![0.5, 1.0, 2.0],
+            vec![0.1, 0.2, 0.3],
+            86,
+            302,
+            proton_charges,
+            1,
+            center_coordinates_bohr,
+            3,
+            numgrid::Partitioning::Ssf,
+        )
+        .unwrap();
+        assert!(!rs.is_empty());
+    }
+
+    // no Bragg radius beyond americium
+    assert!(matches!(
+        numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 }).build(
+            &[96, 1],
+            0,
+            &[(0.0, 0.0, 0.0), (0.0, 0.0, 4.0)]
+        ),
+        Err(numgrid::NumgridError::UnsupportedElement(96))
+    ));
+}
+
+#[test]
+fn atomic_radii() {
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let radial_schemes: Vec<numgrid::RadialScheme> = [
+        (alpha_min_o, 11720.0),
+        (alpha_min_h.clone(), 13.01),
+        (alpha_min_h, 13.01),
+    ]
+    .into_iter()
+    .map(|(alpha_min, alpha_max)| numgrid::RadialScheme::Lmg {
+        alpha_min,
+        alpha_max,
+        radial_precision: 1.0e-12,
+    })
+    .collect();
+
+    let grid = |atomic_radii: numgrid::AtomicRadii| {
+        numgrid::MolecularGridBuilder::new(radial_schemes.clone())
+            .angular_points(86, 302)
+            .hardness(3)
+            .atomic_radii(atomic_radii)
+            .build(
+                &[8, 1, 1],
+                &[(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)],
+            )
+    };
+
+    let integral = |rs: &[(f64, f64, f64)], ws: &[f64]| -> f64 {
+        rs.iter()
+            .zip(ws.iter())
+            .map(|(p, w)| w * water_test_density(p))
+            .sum()
+    };
+
+    let (rs_bragg, ws_bragg, _) = grid(numgrid::AtomicRadii::Bragg).unwrap();
+    let (rs_cordero, ws_cordero, _) = grid(numgrid::AtomicRadii::Cordero).unwrap();
+    assert_ne!(ws_bragg, ws_cordero);
+    assert!(floats_are_same(
+        integral(&rs_cordero, &ws_cordero),
+        5.0,
+        1.0e-6
+    ));
+
+    // smaller hydrogen radius than in the Bragg table, so it is pruned differently
+    let (rs_slater, ws_slater, _) = grid(numgrid::AtomicRadii::Slater).unwrap();
+    assert_ne!(rs_slater.len(), rs_bragg.len());
+    assert!(floats_are_same(
+        integral(&rs_slater, &ws_slater),
+        5.0,
+        1.0e-6
+    ));
+
+    for atomic_radii in [numgrid::AtomicRadii::Treutler, numgrid::AtomicRadii::Uff] {
+        let (rs, ws, _) = grid(atomic_radii).unwrap();
+        assert_ne!(ws, ws_bragg);
+        assert!(floats_are_same(integral(&rs, &ws), 5.0, 1.0e-6));
+    }
+
+    // explicit radii which are equal to the Bragg radii
+    let bohr = 0.529177249;
+    let (rs, ws, _) = grid(numgrid::AtomicRadii::Explicit(vec![
+        0.60 / bohr,
+        0.35 / bohr,
+        0.35 / bohr,
+    ]))
+    .unwrap();
+    assert_eq!(rs.len(), rs_bragg.len());
+    for (w, w_bragg) in ws.iter().zip(ws_bragg.iter()) {
+        assert!(floats_are_same(*w, *w_bragg, 1.0e-10));
+    }
+
+    assert!(grid(numgrid::AtomicRadii::Explicit(vec![1.0, 1.0])).is_err());
+}
+
+#[test]
+fn ghosts_and_point_charges() {
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let grid = |proton_charges: Vec<i32>,
+                center_coordinates_bohr: Vec<(f64, f64, f64)>,
+                center_kinds: Option<Vec<numgrid::CenterKind>>| {
+        let num_centers = proton_charges.len();
+        let mut alpha_min = vec![
+            alpha_min_o.clone(),
+            alpha_min_h.clone(),
+            alpha_min_h.clone(),
+        ];
+        alpha_min.resize(num_centers, HashMap::new());
+        let mut alpha_max = vec![11720.0, 13.01, 13.01];
+        alpha_max.resize(num_centers, 0.0);
+        let radial_schemes = alpha_min
+            .into_iter()
+            .zip(alpha_max)
+            .map(|(alpha_min, alpha_max)| numgrid::RadialScheme::Lmg {
+                alpha_min,
+                alpha_max,
+                radial_precision: 1.0e-12,
+            })
+            .collect();
+        let mut builder = numgrid::MolecularGridBuilder::new(radial_schemes)
+            .angular_points(86, 302)
+            .hardness(3);
+        if let Some(center_kinds) = center_kinds {
+            builder = builder.center_kinds(center_kinds);
+        }
+        builder
+            .build(&proton_charges, &center_coordinates_bohr)
+            .unwrap()
+    };
+
+    let water = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    let (rs_ref, ws_ref, indices_ref) = grid(vec![8, 1, 1], water.clone(), None);
+
+    // a ghost hydrogen gets the same grid as a real one
+    let (rs, ws, indices) = grid(
+        vec![8, 1, 1],
+        water.clone(),
+        Some(vec![
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Ghost,
+        ]),
+    );
+    assert_eq!(rs, rs_ref);
+    assert_eq!(ws, ws_ref);
+    assert_eq!(indices, indices_ref);
+
+    // point charges neither own points nor change the partitioning,
+    // their proton charge is not used
+    let mut centers = water.clone();
+    centers.push((0.5, 0.5, 0.5));
+    centers.push((4.0, 0.0, 0.0));
+    let (rs, ws, indices) = grid(
+        vec![8, 1, 1, 0, 0],
+        centers.clone(),
+        Some(vec![
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::PointCharge,
+            numgrid::CenterKind::PointCharge,
+        ]),
+    );
+    assert_eq!(rs, rs_ref);
+    assert_eq!(ws, ws_ref);
+    assert_eq!(indices, indices_ref);
+
+    // weight gradients with respect to point charges vanish
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 30 })
+        .center_kinds(vec![
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::PointCharge,
+            numgrid::CenterKind::PointCharge,
+        ]);
+    let (_, _, gradients) = builder
+        .build_with_gradient(&[8, 1, 1, 0, 0], 1, &centers)
+        .unwrap();
+    assert!(gradients.iter().all(|g| g.len() == 5));
+    assert!(gradients.iter().any(|g| g[0] != (0.0, 0.0, 0.0)));
+    assert!(gradients
+        .iter()
+        .all(|g| g[3] == (0.0, 0.0, 0.0) && g[4] == (0.0, 0.0, 0.0)));
+
+    let (rs, _, _) = builder
+        .build_with_gradient(&[8, 1, 1, 0, 0], 3, &centers)
+        .unwrap();
+    assert!(rs.is_empty());
+}
+
+// n x n x n water molecules on a slightly distorted cubic lattice
+fn water_cluster(n: usize) -> (Vec<i32>, Vec<(f64, f64, f64)>) {
+    let mut proton_charges = Vec::new();
+    let mut center_coordinates_bohr = Vec::new();
+    for i in 0..n * n * n {
+        let shift = 0.3 * (i as f64).sin();
+        let o = (
+            (i % n) as f64 * 5.8 + shift,
+            ((i / n) % n) as f64 * 5.8 - shift,
+            (i / (n * n)) as f64 * 5.8 + 0.5 * shift,
+        );
+        proton_charges.extend([8, 1, 1]);
+        center_coordinates_bohr.push(o);
+        center_coordinates_bohr.push((o.0 + 1.43, o.1, o.2 + 1.1));
+        center_coordinates_bohr.push((o.0 - 1.43, o.1 + shift, o.2 + 1.1));
+    }
+    (proton_charges, center_coordinates_bohr)
+}
+
+#[test]
+fn neighbor_screening() {
+    let (proton_charges, center_coordinates_bohr) = water_cluster(2);
+
+    for partitioning in [numgrid::Partitioning::Ssf, numgrid::Partitioning::Becke] {
+        let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+            .angular_points(50, 110)
+            .partitioning(partitioning);
+
+        for center_index in [0, 1, 2, 21] {
+            let (rs_ref, ws_ref) = builder
+                .build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap();
+            let (rs, ws) = builder
+                .clone()
+                .screening(true)
+                .build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap();
+            assert_eq!(rs, rs_ref);
+
+            match partitioning {
+                // screening of SSF weights is exact
+                numgrid::Partitioning::Ssf => assert_eq!(ws, ws_ref),
+                numgrid::Partitioning::Becke => {
+                    let (_, ws_unpartitioned) =
+                        numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+                            .angular_points(50, 110)
+                            .build(&[proton_charges[center_index]], 0, &[(0.0, 0.0, 0.0)])
+                            .unwrap();
+                    for ((w, w_ref), w_unpartitioned) in ws.iter().zip(ws_ref).zip(ws_unpartitioned)
+                    {
+                        assert!((w - w_ref).abs() <= 1.0e-10 * w_unpartitioned.abs());
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn becke_screening_error() {
+    // 343 water molecules; the first hydrogen is at a corner of the cluster
+    // and the second one of the central molecule sees neighbors in all
+    // directions; hydrogens have the largest deviations because of the size
+    // adjustment, and a small grid keeps the unscreened reference affordable
+    let (proton_charges, center_coordinates_bohr) = water_cluster(7);
+    assert_eq!(proton_charges.len(), 1029);
+
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 10 })
+        .angular_points(14, 14);
+
+    for center_index in [1, 514] {
+        let (_, ws_ref) = builder
+            .build(&proton_charges, center_index, &center_coordinates_bohr)
+            .unwrap();
+        let (_, ws) = builder
+            .clone()
+            .screening(true)
+            .build(&proton_charges, center_index, &center_coordinates_bohr)
+            .unwrap();
+        let (_, ws_unpartitioned) = builder
+            .build(&[proton_charges[center_index]], 0, &[(0.0, 0.0, 0.0)])
+            .unwrap();
+
+        // the bound documented for Becke screening
+        for ((w, w_ref), w_unpartitioned) in ws.iter().zip(&ws_ref).zip(&ws_unpartitioned) {
+            assert!((w - w_ref).abs() <= 1.0e-10 * w_unpartitioned.abs());
+        }
+    }
+
+    // SSF screening stays exact for a larger cluster
+    let (proton_charges, center_coordinates_bohr) = water_cluster(3);
+    let ssf = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+        .angular_points(50, 110)
+        .partitioning(numgrid::Partitioning::Ssf);
+    for center_index in [39, 1] {
+        assert_eq!(
+            ssf.clone()
+                .screening(true)
+                .build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap(),
+            ssf.build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap()
+        );
+    }
+}
+
+#[test]
+fn batched_grid() {
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let (rs, ws, center_indices) = numgrid::molecular_grid(
+        vec![alpha_min_o, alpha_min_h.clone(), alpha_min_h],
+        vec![11720.0, 13.01, 13.01],
+        1.0e-12,
+        86,
+        302,
+        vec![8, 1, 1],
+        vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)],
+        3,
+        numgrid::Partitioning::Becke,
+    )
+    .unwrap();
+
+    let batches = numgrid::batch_grid(rs.clone(), ws.clone(), center_indices.clone(), 128).unwrap();
+
+    // every point ends up in exactly one batch of its own center
+    let mut seen = vec![false; rs.len()];
+    for (i, batch) in batches.iter().enumerate() {
+        assert!(batch.point_indices.len() <= 128);
+        let last_of_center = batches
+            .get(i + 1)
+            .is_none_or(|next| next.center_index != batch.center_index);
+        if !last_of_center {
+            assert_eq!(batch.point_indices.len(), 128);
+        }
+
+        for (k, &p) in batch.point_indices.iter().enumerate() {
+            assert!(!seen[p]);
+            seen[p] = true;
+            assert_eq!(center_indices[p], batch.center_index);
+            assert_eq!(batch.coordinates_bohr[k], rs[p]);
+            assert_eq!(batch.weights[k], ws[p]);
+
+            let c = rs[p];
+            assert!(c.0 >= batch.box_min.0 && c.0 <= batch.box_max.0);
+            assert!(c.1 >= batch.box_min.1 && c.1 <= batch.box_max.1);
+            assert!(c.2 >= batch.box_min.2 && c.2 <= batch.box_max.2);
+            let d = ((c.0 - batch.sphere_center.0).powi(2)
+                + (c.1 - batch.sphere_center.1).powi(2)
+                + (c.2 - batch.sphere_center.2).powi(2))
+            .sqrt();
+            assert!(d <= batch.sphere_radius);
+        }
+    }
+    assert!(seen.iter().all(|&s| s));
+
+    let integral: f64 = batches
+        .iter()
+        .flat_map(|batch| batch.coordinates_bohr.iter().zip(&batch.weights))
+        .map(|(c, w)| water_test_density(c) * w)
+        .sum();
+    let reference: f64 = rs
+        .iter()
+        .zip(&ws)
+        .map(|(c, w)| water_test_density(c) * w)
+        .sum();
+    assert!(floats_are_same(integral, reference, 1.0e-12));
+
+    // batches are more compact than chunks of the grid in its original order,
+    // and much more compact than chunks of scrambled points
+    let mean_radius = |batches: &[numgrid::Batch]| {
+        batches.iter().map(|b| b.sphere_radius).sum::<f64>() / batches.len() as f64
+    };
+    let chunked = |order: Vec<usize>| -> Vec<numgrid::Batch> {
+        order
+            .chunks(128)
+            .flat_map(|chunk| {
+                let r: Vec<(f64, f64, f64)> = chunk.iter().map(|&p| rs[p]).collect();
+                let w: Vec<f64> = chunk.iter().map(|&p| ws[p]).collect();
+                numgrid::batch_grid(r, w, vec![0; chunk.len()], 128).unwrap()
+            })
+            .collect()
+    };
+    let n = rs.len();
+    let original = chunked((0..n).collect());
+    let scrambled = chunked((0..n).map(|i| (i * 7919) % n).collect());
+    assert!(mean_radius(&batches) < mean_radius(&original));
+    assert!(mean_radius(&batches) < 0.5 * mean_radius(&scrambled));
+
+    assert!(numgrid::batch_grid(rs.clone(), ws.clone(), center_indices.clone(), 0).is_err());
+    assert!(numgrid::batch_grid(rs, ws, vec![0], 128).is_err());
+}
+
+#[test]
+fn weight_screening() {
+    let (rs, ws) = water_grid(numgrid::Partitioning::Becke);
+    let reference: f64 = rs
+        .iter()
+        .zip(&ws)
+        .map(|(c, w)| water_test_density(c) * w)
+        .sum();
+
+    let screened = numgrid::screen_weights(rs.clone(), ws.clone(), 0.0, None).unwrap();
+    assert_eq!(screened.weights, ws);
+    assert_eq!(screened.num_removed, 0);
+    assert_eq!(screened.discarded_weight, 0.0);
+
+    for threshold in [1.0e-15, 1.0e-10, 1.0e-6] {
+        let center_indices: Vec<usize> = (0..rs.len()).map(|i| i % 3).collect();
+        let screened =
+            numgrid::screen_weights(rs.clone(), ws.clone(), threshold, Some(center_indices))
+                .unwrap();
+        assert!(screened.num_removed > 0);
+        assert_eq!(screened.weights.len() + screened.num_removed, ws.len());
+        assert!(screened.weights.iter().all(|w| w.abs() >= threshold));
+        assert!(screened.discarded_weight < threshold * screened.num_removed as f64);
+        for (k, &p) in screened.point_indices.iter().enumerate() {
+            assert_eq!(screened.coordinates_bohr[k], rs[p]);
+            assert_eq!(screened.center_indices.as_ref().unwrap()[k], p % 3);
+        }
+
+        // the error is bounded by the discarded weight
+        let integral: f64 = screened
+            .coordinates_bohr
+            .iter()
+            .zip(&screened.weights)
+            .map(|(c, w)| water_test_density(c) * w)
+            .sum();
+        let max_density = water_test_density(&(0.0, 0.0, 0.0)) * 2.0;
+        assert!((integral - reference).abs() <= screened.discarded_weight * max_density + 1.0e-12);
+    }
+
+    assert!(numgrid::screen_weights(rs.clone(), ws.clone(), -1.0, None).is_err());
+    assert!(numgrid::screen_weights(rs.clone(), vec![1.0], 0.0, None).is_err());
+    assert!(numgrid::screen_weights(rs, ws, 0.0, Some(vec![0])).is_err());
+}
+
+#[test]
+fn atom_grid_in_batches() {
+    let (proton_charges, center_coordinates_bohr) = water_cluster(2);
+
+    for (partitioning, screening) in [
+        (numgrid::Partitioning::Becke, false),
+        (numgrid::Partitioning::Ssf, true),
+    ] {
+        let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+            .angular_points(50, 110)
+            .partitioning(partitioning)
+            .screening(screening);
+
+        for center_index in [0, 1, 21] {
+            let (rs_ref, ws_ref) = builder
+                .build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap();
+
+            for batch_size in [7, 1000, rs_ref.len() + 1] {
+                let mut rs = Vec::new();
+                let mut ws = Vec::new();
+                for (r, w) in builder
+                    .build_batches(
+                        &proton_charges,
+                        center_index,
+                        &center_coordinates_bohr,
+                        batch_size,
+                    )
+                    .unwrap()
+                {
+                    assert_eq!(r.len(), w.len());
+                    assert!(!r.is_empty() && r.len() <= batch_size);
+                    rs.extend(r);
+                    ws.extend(w);
+                }
+
+                // same points in the same order
+                assert_eq!(rs, rs_ref);
+                assert_eq!(ws, ws_ref);
+            }
+        }
+    }
+
+    // point charges have no grid
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+        .center_kinds(vec![
+            numgrid::CenterKind::Atom,
+            numgrid::CenterKind::PointCharge,
+            numgrid::CenterKind::Atom,
+        ]);
+    let centers = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)];
+    assert_eq!(
+        builder
+            .build_batches(&[8, 1, 1], 1, &centers, 100)
+            .unwrap()
+            .count(),
+        0
+    );
+
+    assert!(builder.build_batches(&[8, 1, 1], 0, &centers, 0).is_err());
+    assert!(builder.build_batches(&[8, 1, 1], 3, &centers, 100).is_err());
+}
+
+#[test]
+fn structure_of_arrays() {
+    let (proton_charges, center_coordinates_bohr) = water_cluster(2);
+
+    let builder = numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Kk { num_points: 40 })
+        .angular_points(50, 110)
+        .screening(true);
+
+    for center_index in [0, 1] {
+        let (rs, ws) = builder
+            .build(&proton_charges, center_index, &center_coordinates_bohr)
+            .unwrap();
+        let grid = builder
+            .build_soa(&proton_charges, center_index, &center_coordinates_bohr)
+            .unwrap();
+
+        assert_eq!(grid.len(), ws.len());
+        assert_eq!(grid, numgrid::GridBatch::from((rs.clone(), ws.clone())));
+        assert_eq!(grid.x, rs.iter().map(|r| r.0).collect::<Vec<_>>());
+        assert_eq!(grid.w, ws);
+
+        // batches in both layouts hold the same points
+        let mut batches = builder
+            .build_batches(&proton_charges, center_index, &center_coordinates_bohr, 100)
+            .unwrap();
+        let mut batches_soa = builder
+            .build_batches(&proton_charges, center_index, &center_coordinates_bohr, 100)
+            .unwrap();
+        let mut num_points = 0;
+        while let Some(batch_soa) = batches_soa.next_soa() {
+            let batch = batches.next().unwrap();
+            assert_eq!(batch_soa, numgrid::GridBatch::from(batch));
+            num_points += batch_soa.len();
+        }
+        assert!(batches.next().is_none());
+        assert_eq!(num_points, ws.len());
+    }
+
+    // molecular grid
+    let molecular_builder =
+        numgrid::MolecularGridBuilder::new(vec![
+            numgrid::RadialScheme::Kk { num_points: 40 };
+            proton_charges.len()
+        ])
+        .angular_points(50, 110)
+        .screening(true);
+    let (rs, ws, center_indices) = molecular_builder
+        .build(&proton_charges, &center_coordinates_bohr)
+        .unwrap();
+    let (grid, center_indices_soa) = molecular_builder
+        .build_soa(&proton_charges, &center_coordinates_bohr)
+        .unwrap();
+    assert_eq!(grid, numgrid::GridBatch::from((rs, ws)));
+    assert_eq!(center_indices_soa, center_indices);
+}
+
+#[test]
+fn atom_grid_gradient() {
+    let radial_precision = 1.0e-12;
+
+    let mut alpha_min_o: HashMap<usize, f64> = HashMap::new();
+    alpha_min_o.insert(0, 0.3023);
+    alpha_min_o.insert(1, 0.2753);
+    alpha_min_o.insert(2, 1.185);
+
+    let mut alpha_min_h: HashMap<usize, f64> = HashMap::new();
+    alpha_min_h.insert(0, 0.122);
+    alpha_min_h.insert(1, 0.727);
+
+    let alpha_min = [alpha_min_o, alpha_min_h.clone(), alpha_min_h];
+
+    let alpha_max = [11720.0, 13.01, 13.01];
+    let min_num_angular_points = 14;
+    let max_num_angular_points = 50;
+    let num_centers = 3;
+    let proton_charges = vec![8, 1, 1];
+    let center_coordinates_bohr = vec![(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.2)];
+    let hardness = 3;
+
+    let grid = |center_index: usize, coordinates: Vec<(f64, f64, f64)>| {
+        numgrid::atom_grid(
+            alpha_min[center_index].clone(),
+            alpha_max[center_index],
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            proton_charges.clone(),
+            center_index,
+            coordinates,
+            hardness,
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap()
+    };
+
+    let step = 1.0e-5;
+
+    for center_index in 0..num_centers {
+        let (rs, ws, gradients) = numgrid::atom_grid_gradient(
+            alpha_min[center_index].clone(),
+            alpha_max[center_index],
+            radial_precision,
+            min_num_angular_points,
+            max_num_angular_points,
+            proton_charges.clone(),
+            center_index,
+            center_coordinates_bohr.clone(),
+            hardness,
+        )
+        .unwrap();
+
+        let (rs_reference, ws_reference) = grid(center_index, center_coordinates_bohr.clone());
+        assert_eq!(rs, rs_reference);
+        for (&w, &w_reference) in ws.iter().zip(ws_reference.iter()) {
+            assert!(floats_are_same(w, w_reference, 1.0e-15));
+        }
+
+        for k in 0..num_centers {
+            for ixyz in 0..3 {
+                let displaced = |sign: f64| {
+                    let mut coordinates = center_coordinates_bohr.clone();
+                    match ixyz {
+                        0 => coordinates[k].0 += sign * step,
+                        1 => coordinates[k].1 += sign * step,
+                        _ => coordinates[k].2 += sign * step,
+                    }
+                    grid(center_index, coordinates).1
+                };
+                let ws_plus = displaced(1.0);
+                let ws_minus = displaced(-1.0);
+
+                for (i, gradient) in gradients.iter().enumerate() {
+                    let analytic = match ixyz {
+                        0 => gradient[k].0,
+                        1 => gradient[k].1,
+                        _ => gradient[k].2,
+                    };
+                    let numerical = (ws_plus[i] - ws_minus[i]) / (2.0 * step);
+                    assert!((analytic - numerical).abs() < 1.0e-7 * (1.0 + numerical.abs()));
+                }
+            }
+        }
+
+        // weights are invariant under translation of the whole molecule
+        for gradient in &gradients {
+            let sum = gradient
+                .iter()
+                .fold((0.0, 0.0, 0.0), |s, g| (s.0 + g.0, s.1 + g.1, s.2 + g.2));
+            assert!(sum.0.abs() < 1.0e-10 && sum.1.abs() < 1.0e-10 && sum.2.abs() < 1.0e-10);
+        }
+    }
+}
+
 #[ignore]
 #[test]
 fn benchmark() {
@@ -568,7 +1907,9 @@ fn benchmark() {
             center_index,
             center_coordinates_bohr.clone(),
             hardness,
-        );
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
     }
     println!("time elapsed in benchmark 1: {:?}", start.elapsed());
 }
@@ -678,7 +2019,48 @@ fn another_benchmark() {
             center_index,
             center_coordinates_bohr.clone(),
             hardness,
-        );
+            numgrid::Partitioning::Becke,
+        )
+        .unwrap();
     }
     println!("time elapsed in benchmark 2: {:?}", start.elapsed());
 }
+
+#[ignore]
+#[test]
+fn screening_benchmark() {
+    let mut alpha_min: HashMap<usize, f64> = HashMap::new();
+    alpha_min.insert(0, 0.3023);
+    alpha_min.insert(1, 0.2753);
+    alpha_min.insert(2, 1.185);
+
+    // grid of the oxygen in the middle of clusters of up to 1029 atoms
+    for n in [3, 4, 5, 6, 7] {
+        let (proton_charges, center_coordinates_bohr) = water_cluster(n);
+        let center_index = 3 * ((n / 2) * (n * n + n + 1));
+
+        for partitioning in [numgrid::Partitioning::Ssf, numgrid::Partitioning::Becke] {
+            for screening in [false, true] {
+                let start = Instant::now();
+                numgrid::AtomGridBuilder::new(numgrid::RadialScheme::Lmg {
+                    alpha_min: alpha_min.clone(),
+                    alpha_max: 11720.0,
+                    radial_precision: 1.0e-12,
+                })
+                .angular_points(86, 302)
+                .hardness(3)
+                .partitioning(partitioning)
+                .screening(screening)
+                .build(&proton_charges, center_index, &center_coordinates_bohr)
+                .unwrap();
+                println!(
+                    "{} atoms, {:?}, screening {}: {:?}",
+                    proton_charges.len(),
+                    partitioning,
+                    screening,
+                    start.elapsed()
+                );
+            }
+        }
+    }
+}
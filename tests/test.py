@@ -1,9 +1,15 @@
+import pytest
 from pytest import approx
+import math
 import os
+import sys
+import threading
+from concurrent.futures import ThreadPoolExecutor
+import numpy as np
 import numgrid
 
 
-def _helper(use_bse):
+def _helper(use_bse, basis_set="cc-pVDZ"):
 
     # read reference grid from file
     # we will compare results against this file
@@ -47,7 +53,7 @@ def _helper(use_bse):
     for center_index in range(len(center_coordinates_bohr)):
         if use_bse:
             xyz, w = numgrid.atom_grid_bse(
-                "cc-pVDZ",
+                basis_set,
                 radial_precision,
                 min_num_angular_points,
                 max_num_angular_points,
@@ -78,7 +84,7 @@ def _helper(use_bse):
             assert xyz[i][1] == approx(reference_grid_y_bohr[offset + i], rel=rel_error)
             assert xyz[i][2] == approx(reference_grid_z_bohr[offset + i], rel=rel_error)
         assert w == approx(
-            reference_grid_w[offset : offset + num_points], rel=rel_error
+            np.array(reference_grid_w[offset : offset + num_points]), rel=rel_error
         )
 
         # we only check that call works but for the moment do not verify results
@@ -94,6 +100,20 @@ def _helper(use_bse):
 
         offset += num_points
 
+    xyz, w, center_indices = numgrid.molecular_grid(
+        alpha_min,
+        alpha_max,
+        radial_precision,
+        min_num_angular_points,
+        max_num_angular_points,
+        proton_charges,
+        center_coordinates_bohr,
+        hardness,
+    )
+    assert len(w) == sum(reference_num_points)
+    assert len(center_indices) == len(w)
+    assert w == approx(np.array(reference_grid_w), rel=1.0e-9)
+
 
 def test_h2o_grid_explicit():
     """
@@ -107,3 +127,760 @@ def test_h2o_grid_bse():
     Test H2O grid generation using a basis set fetched via BSE web API.
     """
     _helper(use_bse=True)
+
+
+def test_h2o_grid_bse_file():
+    """
+    Test H2O grid generation using a basis set read from a local BSE JSON file.
+    """
+    _here = os.path.abspath(os.path.dirname(__file__))
+    _helper(use_bse=True, basis_set=os.path.join(_here, "basis", "cc-pvdz.1.json"))
+
+
+def test_h2o_grid_ssf():
+    """
+    Test that SSF partitioning changes the weights but not the points.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    for center_index in range(len(center_coordinates_bohr)):
+        xyz_becke, w_becke = numgrid.atom_grid(
+            {0: 0.3023, 1: 0.2753, 2: 1.185},
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            center_index,
+            center_coordinates_bohr,
+            hardness=3,
+        )
+        xyz_ssf, w_ssf = numgrid.atom_grid(
+            {0: 0.3023, 1: 0.2753, 2: 1.185},
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            center_index,
+            center_coordinates_bohr,
+            hardness=3,
+            partitioning="ssf",
+        )
+        assert np.array_equal(xyz_ssf, xyz_becke)
+        assert not np.array_equal(w_ssf, w_becke)
+
+
+def test_h2o_grid_gradient():
+    """
+    Test that weight gradients are returned for each point and each center.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz, w, dw = numgrid.atom_grid_gradient(
+        {0: 0.122, 1: 0.727},
+        13.01,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        1,
+        center_coordinates_bohr,
+        hardness=3,
+    )
+    assert dw.shape == (len(w), len(center_coordinates_bohr), 3)
+    # translational invariance
+    assert np.abs(dw.sum(axis=1)).max() == approx(0.0, abs=1.0e-10)
+
+
+def test_alpha_min_and_max():
+    """
+    Test reading exponents from basis set files in different formats.
+    """
+    _here = os.path.abspath(os.path.dirname(__file__))
+    for file_name in ["cc-pvdz.1.json", "cc-pvdz.gbs", "cc-pvdz.nw", "basis"]:
+        alpha_min, alpha_max = numgrid.alpha_min_and_max(
+            os.path.join(_here, "basis", file_name), 8
+        )
+        assert alpha_min == {0: 0.3023, 1: 0.2753, 2: 1.185}
+        assert alpha_max == 11720.0
+
+
+def test_invalid_input():
+    """
+    Test that invalid input raises exceptions instead of crashing.
+    """
+    with pytest.raises(ValueError):
+        numgrid.angular_grid(15)
+
+    with pytest.raises(ValueError):
+        numgrid.radial_grid_lmg({0: 0.3023}, 11720.0, 1.0e-12, 0)
+
+    with pytest.raises(ValueError):
+        numgrid.atom_grid(
+            {0: 0.3023}, 11720.0, 1.0e-12, 86, 302, [8], 1, [(0.0, 0.0, 0.0)], 3
+        )
+
+    with pytest.raises(ValueError):
+        numgrid.atom_grid(
+            {0: 0.3023},
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            [8],
+            0,
+            [(0.0, 0.0, 0.0)],
+            3,
+            partitioning="unknown",
+        )
+
+    with pytest.raises(OSError):
+        numgrid.alpha_min_and_max("does-not-exist.gbs", 8)
+
+    with pytest.raises(LookupError):
+        _here = os.path.abspath(os.path.dirname(__file__))
+        numgrid.alpha_min_and_max(os.path.join(_here, "basis", "cc-pvdz.gbs"), 6)
+
+
+def test_radial_grid_ta():
+    """
+    Test Treutler-Ahlrichs radial grid by integrating exp(-r^2) r^2.
+    """
+    r, w = numgrid.radial_grid_ta(100, 8)
+    assert len(r) == 100
+    integral = sum(wi * math.exp(-ri * ri) for ri, wi in zip(r, w))
+    assert integral == approx(math.sqrt(math.pi) / 4.0, rel=1.0e-12)
+
+
+def test_radial_grid_mk():
+    """
+    Test Mura-Knowles radial grid by integrating exp(-r^2) r^2.
+    """
+    r, w = numgrid.radial_grid_mk(100, 8)
+    assert len(r) == 100
+    integral = sum(wi * math.exp(-ri * ri) for ri, wi in zip(r, w))
+    assert integral == approx(math.sqrt(math.pi) / 4.0, rel=1.0e-12)
+
+
+@pytest.mark.parametrize(
+    "radial_grid",
+    [
+        numgrid.radial_grid_em,
+        numgrid.radial_grid_becke,
+        numgrid.radial_grid_mk,
+        numgrid.radial_grid_ta,
+    ],
+)
+def test_atom_grid_from_radial(radial_grid):
+    """
+    Test atom grids built on top of different radial grids by integrating
+    a normalized Gaussian on the oxygen of water.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    integral = 0.0
+    for center_index in range(len(center_coordinates_bohr)):
+        radii, radial_weights = radial_grid(100, proton_charges[center_index])
+        xyz, w = numgrid.atom_grid_from_radial(
+            radii,
+            radial_weights,
+            86,
+            302,
+            proton_charges,
+            center_index,
+            center_coordinates_bohr,
+            hardness=3,
+        )
+        for (x, y, z), wi in zip(xyz, w):
+            integral += wi * (2.0 / math.pi) ** 1.5 * math.exp(-2.0 * (x * x + y * y + z * z))
+    assert integral == approx(1.0, rel=1.0e-5)
+
+
+def test_standard_grid_sg1():
+    """
+    Test SG-1 point counts for water and that unknown names are rejected.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz, w, center_indices = numgrid.standard_molecular_grid(
+        "sg1", proton_charges, center_coordinates_bohr
+    )
+    assert len(w) == 3816 + 2 * 3720
+    assert sum(w) > 0.0
+
+    with pytest.raises(ValueError):
+        numgrid.standard_molecular_grid("sg9", proton_charges, center_coordinates_bohr)
+
+
+def test_custom_pruning():
+    """
+    Test that intervals and Python functions can replace the default pruning.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    def grid(pruning):
+        return numgrid.atom_grid(
+            {0: 0.3023, 1: 0.2753, 2: 1.185},
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            0,
+            center_coordinates_bohr,
+            hardness=3,
+            pruning=pruning,
+        )
+
+    xyz_intervals, w_intervals = grid(([], [302]))
+    xyz_function, w_function = grid(lambda proton_charge, r: 302)
+    assert np.array_equal(xyz_intervals, xyz_function)
+    assert np.array_equal(w_intervals, w_function)
+    assert len(w_function) % 302 == 0
+
+    with pytest.raises(ValueError):
+        grid(lambda proton_charge, r: 303)
+    with pytest.raises(ValueError):
+        grid(([0.5], [14]))
+    with pytest.raises(ValueError):
+        grid("unknown")
+
+
+def test_molecular_grid_pruning():
+    """
+    Test that the pruning argument of molecular_grid gives the same grid as
+    atom_grid with the same pruning for each center.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    pruning = ([0.25, 0.5, 1.0], [14, 50, 110, 302])
+
+    xyz, w, center_indices = numgrid.molecular_grid(
+        alpha_min,
+        alpha_max,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        center_coordinates_bohr,
+        hardness=3,
+        pruning=pruning,
+    )
+
+    for center_index in range(len(proton_charges)):
+        xyz_atom, w_atom = numgrid.atom_grid(
+            alpha_min[center_index],
+            alpha_max[center_index],
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            center_index,
+            center_coordinates_bohr,
+            hardness=3,
+            pruning=pruning,
+        )
+        mask = center_indices == center_index
+        assert np.array_equal(xyz[mask], xyz_atom)
+        assert np.array_equal(w[mask], w_atom)
+
+
+def test_quality_presets():
+    """
+    Test that named quality levels give increasingly large grids.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+
+    num_points = 0
+    for quality in ["coarse", "medium", "fine", "ultrafine"]:
+        xyz, w, center_indices = numgrid.molecular_grid_quality(
+            quality, alpha_min, alpha_max, proton_charges, center_coordinates_bohr
+        )
+        assert len(w) > num_points
+        num_points = len(w)
+
+    assert numgrid.grid_parameters("fine", 8) == (1.0e-12, 86, 302, 3)
+
+    with pytest.raises(ValueError):
+        numgrid.grid_parameters("extreme", 8)
+
+
+def test_atomic_radii():
+    """
+    Test that radius tables and explicit radii change the partitioning.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+
+    def grid(atomic_radii):
+        return numgrid.molecular_grid(
+            alpha_min,
+            alpha_max,
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            center_coordinates_bohr,
+            hardness=3,
+            atomic_radii=atomic_radii,
+        )
+
+    xyz_bragg, w_bragg, _ = grid("bragg")
+    xyz_cordero, w_cordero, _ = grid("cordero")
+    assert not np.array_equal(w_bragg, w_cordero)
+    xyz_slater, w_slater, _ = grid("slater")
+    assert len(w_slater) != len(w_bragg)
+    for radii in ["treutler", "uff"]:
+        xyz, w, _ = grid(radii)
+        assert not np.array_equal(w, w_bragg)
+
+    bohr = 0.529177249
+    xyz, w, _ = grid([0.60 / bohr, 0.35 / bohr, 0.35 / bohr])
+    assert w == approx(w_bragg, rel=1.0e-10)
+
+    with pytest.raises(ValueError):
+        grid([1.0, 1.0])
+    with pytest.raises(ValueError):
+        grid("unknown")
+
+
+def test_ghosts_and_point_charges():
+    """
+    Test that ghosts get grids and point charges are ignored.
+    """
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz_ref, w_ref, indices_ref = numgrid.molecular_grid(
+        alpha_min,
+        alpha_max,
+        1.0e-12,
+        86,
+        302,
+        [8, 1, 1],
+        center_coordinates_bohr,
+        hardness=3,
+    )
+
+    xyz, w, indices = numgrid.molecular_grid(
+        alpha_min + [{}],
+        alpha_max + [0.0],
+        1.0e-12,
+        86,
+        302,
+        [8, 1, 1, 0],
+        center_coordinates_bohr + [(0.5, 0.5, 0.5)],
+        hardness=3,
+        center_kinds=["atom", "atom", "ghost", "point_charge"],
+    )
+    assert np.array_equal(xyz, xyz_ref)
+    assert np.array_equal(w, w_ref)
+    assert np.array_equal(indices, indices_ref)
+
+    with pytest.raises(ValueError):
+        numgrid.molecular_grid(
+            alpha_min,
+            alpha_max,
+            1.0e-12,
+            86,
+            302,
+            [8, 1, 1],
+            center_coordinates_bohr,
+            hardness=3,
+            center_kinds=["atom", "atom"],
+        )
+
+
+def test_neighbor_screening():
+    """
+    Test that screening does not change SSF weights and changes Becke weights
+    only slightly.
+    """
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ] * 2
+    alpha_max = [11720.0, 13.01, 13.01] * 2
+    center_coordinates_bohr = [
+        (0.0, 0.0, 0.0),
+        (1.43, 0.0, 1.1),
+        (-1.43, 0.0, 1.1),
+        (0.0, 5.8, 0.0),
+        (1.43, 5.8, 1.1),
+        (-1.43, 5.8, 1.1),
+    ]
+
+    for partitioning in ["ssf", "becke"]:
+        xyz_ref, w_ref, _ = numgrid.molecular_grid(
+            alpha_min,
+            alpha_max,
+            1.0e-12,
+            86,
+            302,
+            [8, 1, 1, 8, 1, 1],
+            center_coordinates_bohr,
+            hardness=3,
+            partitioning=partitioning,
+        )
+        xyz, w, _ = numgrid.molecular_grid(
+            alpha_min,
+            alpha_max,
+            1.0e-12,
+            86,
+            302,
+            [8, 1, 1, 8, 1, 1],
+            center_coordinates_bohr,
+            hardness=3,
+            partitioning=partitioning,
+            screening=True,
+        )
+        assert np.array_equal(xyz, xyz_ref)
+        if partitioning == "ssf":
+            assert np.array_equal(w, w_ref)
+        else:
+            assert sum(w) == pytest.approx(sum(w_ref), rel=1.0e-10)
+
+
+def test_batch_grid():
+    """
+    Test that batches cover the grid and stay within their bounds.
+    """
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz, w, indices = numgrid.molecular_grid(
+        alpha_min,
+        alpha_max,
+        1.0e-12,
+        86,
+        302,
+        [8, 1, 1],
+        center_coordinates_bohr,
+        hardness=3,
+    )
+
+    batches = numgrid.batch_grid(xyz, w, indices, max_batch_size=128)
+
+    assert sorted(p for batch in batches for p in batch.point_indices) == list(
+        range(len(w))
+    )
+    for batch in batches:
+        assert len(batch.weights) <= 128
+        assert all(indices[p] == batch.center_index for p in batch.point_indices)
+        for x, y, z in batch.coordinates_bohr:
+            assert batch.box_min[0] <= x <= batch.box_max[0]
+            assert batch.box_min[1] <= y <= batch.box_max[1]
+            assert batch.box_min[2] <= z <= batch.box_max[2]
+    assert sum(sum(batch.weights) for batch in batches) == pytest.approx(sum(w))
+
+    with pytest.raises(ValueError):
+        numgrid.batch_grid(xyz, w, indices, max_batch_size=0)
+
+
+def test_screen_weights():
+    """
+    Test that screening removes small weights and reports them.
+    """
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz, w, indices = numgrid.molecular_grid(
+        alpha_min,
+        alpha_max,
+        1.0e-12,
+        86,
+        302,
+        [8, 1, 1],
+        center_coordinates_bohr,
+        hardness=3,
+    )
+
+    screened = numgrid.screen_weights(xyz, w, 1.0e-10, indices)
+
+    assert screened.num_removed > 0
+    assert len(screened.weights) + screened.num_removed == len(w)
+    assert all(abs(x) >= 1.0e-10 for x in screened.weights)
+    assert screened.center_indices == [indices[p] for p in screened.point_indices]
+    assert screened.discarded_weight == pytest.approx(
+        sum(abs(x) for x in w if abs(x) < 1.0e-10)
+    )
+
+    with pytest.raises(ValueError):
+        numgrid.screen_weights(xyz, w, -1.0)
+
+
+def test_atom_grid_batches():
+    """
+    Test that the batches of an atom grid add up to the full grid.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+    arguments = (
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        0,
+        center_coordinates_bohr,
+    )
+
+    xyz, w = numgrid.atom_grid(*arguments, hardness=3)
+
+    batches = list(numgrid.atom_grid_batches(*arguments, hardness=3, batch_size=1000))
+    assert all(len(w_batch) <= 1000 for _, w_batch in batches)
+    assert np.array_equal(np.concatenate([xyz_batch for xyz_batch, _ in batches]), xyz)
+    assert np.array_equal(np.concatenate([w_batch for _, w_batch in batches]), w)
+
+    with pytest.raises(ValueError):
+        numgrid.atom_grid_batches(*arguments, hardness=3, batch_size=0)
+
+
+def test_structure_of_arrays():
+    """
+    Test that soa=True returns the same grids as separate x, y, z, and
+    weight arrays.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+    arguments = (
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        0,
+        center_coordinates_bohr,
+    )
+
+    xyz, w = numgrid.atom_grid(*arguments, hardness=3)
+    x, y, z, w_soa = numgrid.atom_grid(*arguments, hardness=3, soa=True)
+    assert x.shape == (len(w),)
+    assert x.flags["C_CONTIGUOUS"]
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+
+    batches = list(
+        numgrid.atom_grid_batches(*arguments, hardness=3, batch_size=1000, soa=True)
+    )
+    assert np.array_equal(np.concatenate([batch[0] for batch in batches]), x)
+    assert np.array_equal(np.concatenate([batch[3] for batch in batches]), w)
+
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    molecular_arguments = (
+        alpha_min,
+        alpha_max,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        center_coordinates_bohr,
+    )
+    xyz, w, center_indices = numgrid.molecular_grid(*molecular_arguments, hardness=3)
+    x, y, z, w_soa, center_indices_soa = numgrid.molecular_grid(
+        *molecular_arguments, hardness=3, soa=True
+    )
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+    assert np.array_equal(center_indices_soa, center_indices)
+
+    xyz, w = numgrid.atom_grid_quality("fine", *arguments[:2], *arguments[5:])
+    x, y, z, w_soa = numgrid.atom_grid_quality(
+        "fine", *arguments[:2], *arguments[5:], soa=True
+    )
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+
+    xyz, w, center_indices = numgrid.molecular_grid_quality(
+        "fine", alpha_min, alpha_max, proton_charges, center_coordinates_bohr
+    )
+    x, y, z, w_soa, center_indices_soa = numgrid.molecular_grid_quality(
+        "fine", alpha_min, alpha_max, proton_charges, center_coordinates_bohr, soa=True
+    )
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+    assert np.array_equal(center_indices_soa, center_indices)
+
+    xyz, w = numgrid.standard_atom_grid("sg1", proton_charges, 0, center_coordinates_bohr)
+    x, y, z, w_soa = numgrid.standard_atom_grid(
+        "sg1", proton_charges, 0, center_coordinates_bohr, soa=True
+    )
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+
+    xyz, w, center_indices = numgrid.standard_molecular_grid(
+        "sg1", proton_charges, center_coordinates_bohr
+    )
+    x, y, z, w_soa, center_indices_soa = numgrid.standard_molecular_grid(
+        "sg1", proton_charges, center_coordinates_bohr, soa=True
+    )
+    assert np.array_equal(np.column_stack((x, y, z)), xyz)
+    assert np.array_equal(w_soa, w)
+    assert np.array_equal(center_indices_soa, center_indices)
+
+
+def test_numpy_arrays():
+    """
+    Test that grids are returned as contiguous NumPy arrays and that arrays
+    are accepted as input.
+    """
+    proton_charges = [8, 1, 1]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    xyz, w = numgrid.angular_grid(14)
+    assert xyz.shape == (14, 3)
+    assert w.shape == (14,)
+    assert xyz.dtype == np.float64
+    assert xyz.flags["C_CONTIGUOUS"]
+
+    r, w = numgrid.radial_grid_kk(100)
+    assert r.shape == (100,)
+    assert w.shape == (100,)
+
+    xyz, w = numgrid.atom_grid(
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        0,
+        center_coordinates_bohr,
+        hardness=3,
+    )
+    assert xyz.shape == (len(w), 3)
+    assert xyz.flags["C_CONTIGUOUS"]
+
+    xyz_array, w_array = numgrid.atom_grid(
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        11720.0,
+        1.0e-12,
+        86,
+        302,
+        proton_charges,
+        0,
+        np.array(center_coordinates_bohr),
+        hardness=3,
+    )
+    assert np.array_equal(xyz_array, xyz)
+    assert np.array_equal(w_array, w)
+
+    with pytest.raises(ValueError):
+        numgrid.atom_grid(
+            {0: 0.3023, 1: 0.2753, 2: 1.185},
+            11720.0,
+            1.0e-12,
+            86,
+            302,
+            proton_charges,
+            0,
+            np.zeros((3, 2)),
+            hardness=3,
+        )
+
+
+def test_gil_released():
+    """
+    Test that other Python threads can run while grids are computed and that
+    grids built concurrently from threads are correct.
+    """
+    alpha_min = [
+        {0: 0.3023, 1: 0.2753, 2: 1.185},
+        {0: 0.122, 1: 0.727},
+        {0: 0.122, 1: 0.727},
+    ]
+    alpha_max = [11720.0, 13.01, 13.01]
+    center_coordinates_bohr = [(0.0, 0.0, 0.0), (1.43, 0.0, 1.1), (-1.43, 0.0, 1.1)]
+
+    def grid(center_index):
+        return numgrid.atom_grid(
+            alpha_min[center_index],
+            alpha_max[center_index],
+            1.0e-14,
+            302,
+            974,
+            [8, 1, 1],
+            center_index,
+            center_coordinates_bohr,
+            hardness=3,
+        )
+
+    # with a huge switch interval the interpreter never takes the GIL away
+    # from the main thread, so the other thread can only run while a grid
+    # call has released it
+    go = threading.Event()
+    ran = threading.Event()
+
+    def other():
+        go.wait()
+        ran.set()
+
+    switch_interval = sys.getswitchinterval()
+    sys.setswitchinterval(1000.0)
+    try:
+        thread = threading.Thread(target=other)
+        thread.start()
+        go.set()
+        num_calls = 0
+        while not ran.is_set() and num_calls < 100:
+            grid(0)
+            num_calls += 1
+        assert ran.is_set()
+    finally:
+        sys.setswitchinterval(switch_interval)
+        thread.join()
+
+    reference = [grid(center_index) for center_index in range(3)]
+    with ThreadPoolExecutor(max_workers=3) as executor:
+        results = list(executor.map(grid, range(3)))
+    for (xyz, w), (xyz_ref, w_ref) in zip(results, reference):
+        assert np.array_equal(xyz, xyz_ref)
+        assert np.array_equal(w, w_ref)